@@ -0,0 +1,103 @@
+//! Throughput of the parser on a generated mesh, in MB/s. The lexing stage is
+//! also compared with the allocating tokenizer it replaced, which built a
+//! `Vec<String>` per line and per face corner.
+//!
+//! Run with `cargo bench --bench parse`.
+
+use std::hint::black_box;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use scop::parser::{self, Token};
+
+/// Vertices per side of the generated grid.
+const GRID_SIZE: usize = 600;
+const ITERATIONS: usize = 5;
+
+fn main() {
+    let source = grid(GRID_SIZE);
+    println!("{} bytes, best of {ITERATIONS} runs", source.len());
+    report("allocating lexer", &source, || allocating_lex(&source));
+    report("borrowing lexer", &source, || borrowing_lex(&source));
+    report("parse_str", &source, || {
+        parser::parse_str(&source, Path::new(".")).expect("Generated source is valid");
+    });
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    report(&format!("{threads} threads"), &source, || {
+        parser::parse_str_parallel(&source, Path::new("."), threads).expect("Generated source is valid");
+    });
+}
+
+/// Square grid with texture coordinates and normals, split in triangles.
+fn grid(size: usize) -> String {
+    let mut source = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (x as f32 / size as f32, y as f32 / size as f32);
+            source += &format!("v {:.6} {:.6} {:.6}\n", u * 2.0 - 1.0, v * 2.0 - 1.0, (u * v).sin());
+            source += &format!("vt {u:.6} {v:.6}\n");
+            source += "vn 0.000000 0.000000 1.000000\n";
+        }
+    }
+    for y in 0..size - 1 {
+        for x in 0..size - 1 {
+            let a = y * size + x + 1;
+            let (b, c, d) = (a + 1, a + size, a + size + 1);
+            source += &format!("f {a}/{a}/{a} {b}/{b}/{b} {d}/{d}/{d}\n");
+            source += &format!("f {a}/{a}/{a} {d}/{d}/{d} {c}/{c}/{c}\n");
+        }
+    }
+    source
+}
+
+fn allocating_lex(source: &str) {
+    for line in source.lines() {
+        let tokens: Vec<String> = line.split(char::is_whitespace)
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect();
+        for token in tokens.iter().skip(1) {
+            if tokens[0] == "f" {
+                let parameters: Vec<String> = token.split('/').map(str::to_string).collect();
+                for parameter in &parameters {
+                    black_box(parameter.parse::<isize>().ok());
+                }
+            } else {
+                black_box(token.parse::<f32>().ok());
+            }
+        }
+    }
+}
+
+fn borrowing_lex(source: &str) {
+    let mut tokens = vec![];
+    for line in source.lines() {
+        Token::lex(line, &mut tokens);
+        let Some((identifier, arguments)) = tokens.split_first() else {
+            continue;
+        };
+        for token in arguments {
+            if matches!(identifier, Token::Face) {
+                for parameter in token.extract_value().unwrap_or_default().split('/') {
+                    black_box(parameter.parse::<isize>().ok());
+                }
+            } else {
+                black_box(token.parse_f32().ok());
+            }
+        }
+    }
+}
+
+fn report(label: &str, source: &str, mut run: impl FnMut()) {
+    let best = (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+    let throughput = source.len() as f64 / 1e6 / best.as_secs_f64();
+    println!("{label:>16}: {:>8.2} ms, {throughput:>8.1} MB/s", best.as_secs_f64() * 1e3);
+}
@@ -1,4 +1,6 @@
 mod engine;
+mod mesh;
+mod transform;
 
 use std::sync::Arc;
 use winit::application::ApplicationHandler;
@@ -6,6 +8,8 @@ use winit::event::WindowEvent;
 use winit::event_loop::ActiveEventLoop;
 use winit::window::{Window, WindowId};
 use engine::Engine;
+use mesh::Mesh;
+use scop::object::{IndexedMesh, Material};
 
 const WIDTH: u32 = 800;
 const HEIGHT: u32 = 600;
@@ -13,13 +17,15 @@ const HEIGHT: u32 = 600;
 pub struct Application {
     window: Option<Arc<Window>>,
     engine: Option<Engine>,
+    mesh: Mesh,
 }
 
 impl Application {
-    pub fn new() -> Self {
+    pub fn new(indexed_mesh: &IndexedMesh, materials: &[Material]) -> Self {
         Self {
             window: None,
             engine: None,
+            mesh: Mesh::new(indexed_mesh, materials),
         }
     }
 }
@@ -38,20 +44,26 @@ impl ApplicationHandler for Application {
         }
         if self.engine.is_none() {
             if let Some(window) = self.window.as_ref() {
-                let engine = Engine::new(event_loop, window);
+                let engine = Engine::new(event_loop, window, &self.mesh);
                 self.engine = Some(engine)
             }
         }
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
         match event {
             WindowEvent::CloseRequested => {
                 println!("WINDOW_EVENT: CloseRequested");
                 event_loop.exit();
             }
+            WindowEvent::Resized(_) => {
+                if let Some(engine) = self.engine.as_mut() {
+                    engine.invalidate_swap_chain();
+                }
+            }
             WindowEvent::RedrawRequested => {
-                if let Some(window) = &self.window {
+                if let (Some(window), Some(engine)) = (&self.window, self.engine.as_mut()) {
+                    engine.draw_frame(window);
                     window.request_redraw();
                 }
             }
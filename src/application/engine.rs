@@ -1,7 +1,9 @@
 extern crate vulkano;
 
 use ahash::HashSet;
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Instant;
 use winit::{
     window::{
         Window,
@@ -29,29 +31,41 @@ use vulkano::{instance::{
     QueueCreateInfo,
     QueueFlags,
     physical::PhysicalDevice,
-}, swapchain::Surface, Version, VulkanLibrary};
+}, swapchain::Surface, Validated, Version, VulkanError, VulkanLibrary};
+use vulkano::buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo};
+use vulkano::command_buffer::allocator::StandardCommandBufferAllocator;
 use vulkano::format::Format;
-use vulkano::image::{Image, ImageLayout, ImageUsage};
+use vulkano::image::{Image, ImageCreateInfo, ImageLayout, ImageType, ImageUsage};
 use vulkano::image::view::ImageView;
+use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
 use vulkano::pipeline::graphics::GraphicsPipelineCreateInfo;
-use vulkano::pipeline::graphics::input_assembly::InputAssemblyState;
-use vulkano::pipeline::graphics::vertex_input::VertexInputState;
+use vulkano::pipeline::graphics::depth_stencil::{DepthState, DepthStencilState};
+use vulkano::pipeline::graphics::input_assembly::{InputAssemblyState, PrimitiveTopology};
+use vulkano::pipeline::graphics::vertex_input::{Vertex, VertexDefinition};
 use vulkano::pipeline::graphics::viewport::{Scissor, Viewport, ViewportState};
-use vulkano::pipeline::{DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo};
+use vulkano::pipeline::{DynamicState, GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo};
 use vulkano::pipeline::graphics::color_blend::{ColorBlendAttachmentState, ColorBlendState};
 use vulkano::pipeline::graphics::multisample::MultisampleState;
 use vulkano::pipeline::graphics::rasterization::{CullMode, FrontFace, RasterizationState};
 use vulkano::pipeline::layout::{PipelineDescriptorSetLayoutCreateInfo};
 use vulkano::render_pass::{AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp, Framebuffer, FramebufferCreateInfo, RenderPass, RenderPassCreateInfo, Subpass, SubpassDescription};
-use vulkano::swapchain::{ColorSpace, CompositeAlpha, PresentMode, SurfaceCapabilities, Swapchain, SwapchainCreateInfo};
-use vulkano::sync::Sharing;
-use vulkano_shaders;
+use vulkano::swapchain::{self, ColorSpace, CompositeAlpha, PresentMode, SurfaceCapabilities, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo};
+use vulkano::sync::{self, GpuFuture, Sharing};
 use crate::application::{HEIGHT, WIDTH};
+use crate::application::mesh::{Mesh, MeshVertex, Topology};
+use crate::application::transform;
 
 const VALIDATION_LAYERS: &[&str] = &[
     "VK_LAYER_KHRONOS_validation",
 ];
 
+const DEPTH_FORMAT: Format = Format::D16_UNORM;
+
+const FIELD_OF_VIEW: f32 = std::f32::consts::FRAC_PI_4;
+const CAMERA_DISTANCE: f32 = 3.0;
+const ROTATION_SPEED: f32 = 0.5;
+
 const DEVICE_EXTENSIONS: DeviceExtensions = DeviceExtensions {
     khr_swapchain: true,
     ..DeviceExtensions::empty()
@@ -120,6 +134,19 @@ impl SwapChainSupport {
     }
 }
 
+#[derive(BufferContents, Clone, Copy)]
+#[repr(C)]
+struct PushConstants {
+    mvp: transform::Matrix4,
+    model: transform::Matrix4,
+}
+
+/// Pipeline drawing a slice of the index buffer with its own topology.
+struct Draw {
+    pipeline: Arc<GraphicsPipeline>,
+    indices: Range<u32>,
+}
+
 pub struct Engine {
     instance: Arc<Instance>,
     debug_messenger: Option<DebugUtilsMessenger>,
@@ -131,12 +158,20 @@ pub struct Engine {
     swap_chain: Arc<Swapchain>,
     swap_chain_images: Vec<Arc<Image>>,
     render_pass: Arc<RenderPass>,
-    graphics_pipeline: Arc<GraphicsPipeline>,
-    framebuffers: Vec<Arc<Framebuffer>>
+    draws: Vec<Draw>,
+    framebuffers: Vec<Arc<Framebuffer>>,
+    memory_allocator: Arc<StandardMemoryAllocator>,
+    command_buffer_allocator: StandardCommandBufferAllocator,
+    vertex_buffer: Subbuffer<[MeshVertex]>,
+    index_buffer: Subbuffer<[u32]>,
+    normalization: transform::Matrix4,
+    start_time: Instant,
+    recreate_swap_chain: bool,
+    previous_frame_end: Option<Box<dyn GpuFuture>>,
 }
 
 impl Engine {
-    pub fn new(event_loop: &ActiveEventLoop, window: &Arc<Window>) -> Self {
+    pub fn new(event_loop: &ActiveEventLoop, window: &Arc<Window>, mesh: &Mesh) -> Self {
         let instance = Self::create_instance(&event_loop);
         let debug_messenger = Self::debug_messenger(instance.clone());
         let surface = Self::create_surface(&instance, window);
@@ -152,16 +187,31 @@ impl Engine {
             &device,
         );
         let render_pass = Self::create_render_pass(&device, &swap_chain);
-        let graphics_pipeline = Self::create_graphics_pipeline(
-            &device,
-            swap_chain.image_extent(),
-            &render_pass,
-        );
+        let draws = mesh.draws.iter()
+            .map(|draw| Draw {
+                pipeline: Self::create_graphics_pipeline(
+                    &device,
+                    swap_chain.image_extent(),
+                    &render_pass,
+                    draw.topology,
+                ),
+                indices: draw.indices.clone(),
+            })
+            .collect();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
         let framebuffers = Self::create_framebuffers(
             &render_pass,
             &swap_chain_images,
             swap_chain.image_extent(),
+            &memory_allocator,
         );
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        );
+        let (vertex_buffer, index_buffer) = Self::create_mesh_buffers(&memory_allocator, mesh);
+        let normalization = Self::normalization_matrix(mesh);
+        let previous_frame_end = Some(sync::now(device.clone()).boxed());
         Self {
             instance,
             debug_messenger,
@@ -173,11 +223,70 @@ impl Engine {
             swap_chain,
             swap_chain_images,
             render_pass,
-            graphics_pipeline,
+            draws,
             framebuffers,
+            memory_allocator,
+            command_buffer_allocator,
+            vertex_buffer,
+            index_buffer,
+            normalization,
+            start_time: Instant::now(),
+            recreate_swap_chain: false,
+            previous_frame_end,
         }
     }
 
+    pub fn invalidate_swap_chain(&mut self) {
+        self.recreate_swap_chain = true;
+    }
+
+    pub fn draw_frame(&mut self, window: &Window) {
+        let image_extent: [u32; 2] = window.inner_size().into();
+        if image_extent.contains(&0) {
+            return;
+        }
+        if let Some(previous_frame_end) = self.previous_frame_end.as_mut() {
+            previous_frame_end.cleanup_finished();
+        }
+        if self.recreate_swap_chain {
+            self.recreate_swap_chain(image_extent);
+        }
+        let (image_index, suboptimal, acquire_future) = match swapchain::acquire_next_image(
+            self.swap_chain.clone(),
+            None,
+        ).map_err(Validated::unwrap) {
+            Ok(res) => res,
+            Err(VulkanError::OutOfDate) => {
+                self.recreate_swap_chain = true;
+                return;
+            }
+            Err(e) => panic!("Failed to acquire next image: {e}"),
+        };
+        if suboptimal {
+            self.recreate_swap_chain = true;
+        }
+        let command_buffer = self.record_command_buffer(image_index as usize);
+        let future = self.previous_frame_end
+            .take()
+            .expect("Previous frame future is missing")
+            .join(acquire_future)
+            .then_execute(self.graphics_queue.clone(), command_buffer)
+            .expect("Failed to execute command buffer")
+            .then_swapchain_present(
+                self.present_queue.clone(),
+                SwapchainPresentInfo::swapchain_image_index(self.swap_chain.clone(), image_index),
+            )
+            .then_signal_fence_and_flush();
+        self.previous_frame_end = match future.map_err(Validated::unwrap) {
+            Ok(future) => Some(future.boxed()),
+            Err(VulkanError::OutOfDate) => {
+                self.recreate_swap_chain = true;
+                Some(sync::now(self.device.clone()).boxed())
+            }
+            Err(e) => panic!("Failed to flush future: {e}"),
+        };
+    }
+
     // INSTANCE CREATION
 
     fn create_instance(event_loop: &ActiveEventLoop) -> Arc<Instance> {
@@ -371,31 +480,62 @@ impl Engine {
         }
     }
 
+    fn recreate_swap_chain(&mut self, image_extent: [u32; 2]) {
+        let (swap_chain, swap_chain_images) = self.swap_chain
+            .recreate(SwapchainCreateInfo {
+                image_extent,
+                ..self.swap_chain.create_info()
+            })
+            .expect("Failed to recreate swap chain");
+        self.framebuffers = Self::create_framebuffers(
+            &self.render_pass,
+            &swap_chain_images,
+            swap_chain.image_extent(),
+            &self.memory_allocator,
+        );
+        self.swap_chain = swap_chain;
+        self.swap_chain_images = swap_chain_images;
+        self.recreate_swap_chain = false;
+    }
+
     // RENDER PASS
 
     fn create_render_pass(device: &Arc<Device>, swap_chain: &Arc<Swapchain>) -> Arc<RenderPass>{
-        let color_attachments = vec![
+        let attachments = vec![
             AttachmentDescription {
                 format: swap_chain.image_format(),
                 load_op: AttachmentLoadOp::Clear,
                 store_op: AttachmentStoreOp::Store,
                 final_layout: ImageLayout::PresentSrc,
                 ..Default::default()
-            }
+            },
+            AttachmentDescription {
+                format: DEPTH_FORMAT,
+                load_op: AttachmentLoadOp::Clear,
+                store_op: AttachmentStoreOp::DontCare,
+                final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                ..Default::default()
+            },
         ];
         let color_attachments_ref = AttachmentReference {
             attachment: 0,
             layout: ImageLayout::ColorAttachmentOptimal,
             ..Default::default()
         };
+        let depth_attachment_ref = AttachmentReference {
+            attachment: 1,
+            layout: ImageLayout::DepthStencilAttachmentOptimal,
+            ..Default::default()
+        };
         let subpasses = vec![
             SubpassDescription {
                 color_attachments: vec![Some(color_attachments_ref)],
+                depth_stencil_attachment: Some(depth_attachment_ref),
                 ..Default::default()
             }
         ];
         let create_info = RenderPassCreateInfo {
-            attachments: color_attachments,
+            attachments,
             subpasses,
             ..Default::default()
         };
@@ -411,6 +551,7 @@ impl Engine {
         device: &Arc<Device>,
         image_extent: [u32; 2],
         render_pass: &Arc<RenderPass>,
+        topology: Topology,
     ) -> Arc<GraphicsPipeline> {
         mod vertex_shader {
             vulkano_shaders::shader! {
@@ -435,6 +576,9 @@ impl Engine {
             .entry_point("main")
             .expect("Failed to set fragment shader entrypoint");
 
+        let vertex_input_state = MeshVertex::per_vertex()
+            .definition(&vert_shader_module.info().input_interface)
+            .expect("Failed to create vertex input state");
         let stages = [
             PipelineShaderStageCreateInfo::new(vert_shader_module),
             PipelineShaderStageCreateInfo::new(frag_shader_module),
@@ -454,7 +598,7 @@ impl Engine {
         dynamic_state.insert(DynamicState::Viewport);
         dynamic_state.insert(DynamicState::Scissor);
         let rasterization_state = Some(RasterizationState {
-            cull_mode: CullMode::Back,
+            cull_mode: CullMode::None,
             front_face: FrontFace::Clockwise,
             ..Default::default()
         });
@@ -473,13 +617,23 @@ impl Engine {
             .expect("Failed to get subpass from render pass");
         let create_info = GraphicsPipelineCreateInfo {
             stages: stages.into_iter().collect(),
-            vertex_input_state: Some(VertexInputState::new()),
-            input_assembly_state: Some(InputAssemblyState::default()),
+            vertex_input_state: Some(vertex_input_state),
+            input_assembly_state: Some(InputAssemblyState {
+                topology: match topology {
+                    Topology::Triangles => PrimitiveTopology::TriangleList,
+                    Topology::Lines => PrimitiveTopology::LineList,
+                    Topology::Points => PrimitiveTopology::PointList,
+                },
+                ..Default::default()
+            }),
             tessellation_state: None,
             viewport_state,
             rasterization_state,
             multisample_state,
-            depth_stencil_state: None,
+            depth_stencil_state: Some(DepthStencilState {
+                depth: Some(DepthState::simple()),
+                ..Default::default()
+            }),
             color_blend_state,
             dynamic_state,
             subpass: Some(subpass.into()),
@@ -498,12 +652,14 @@ impl Engine {
         render_pass: &Arc<RenderPass>,
         swap_chain_images: &Vec<Arc<Image>>,
         image_extent: [u32; 2],
+        memory_allocator: &Arc<StandardMemoryAllocator>,
     ) -> Vec<Arc<Framebuffer>> {
+        let depth_view = Self::create_depth_view(memory_allocator, image_extent);
         swap_chain_images.iter().map(|image| {
             let view = ImageView::new_default(image.clone())
                 .expect("Failed to create image view");
             let create_info = FramebufferCreateInfo {
-                attachments: vec![view],
+                attachments: vec![view, depth_view.clone()],
                 extent: image_extent,
                 layers: 1,
                 ..Default::default()
@@ -515,6 +671,141 @@ impl Engine {
         }).collect::<Vec<_>>()
     }
 
+    fn create_depth_view(
+        memory_allocator: &Arc<StandardMemoryAllocator>,
+        image_extent: [u32; 2],
+    ) -> Arc<ImageView> {
+        let create_info = ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format: DEPTH_FORMAT,
+            extent: [image_extent[0], image_extent[1], 1],
+            usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT,
+            ..Default::default()
+        };
+        let image = Image::new(
+            memory_allocator.clone(),
+            create_info,
+            AllocationCreateInfo::default(),
+        ).expect("Failed to create depth image");
+        ImageView::new_default(image)
+            .expect("Failed to create depth image view")
+    }
+
+    // BUFFERS
+
+    fn create_mesh_buffers(
+        memory_allocator: &Arc<StandardMemoryAllocator>,
+        mesh: &Mesh,
+    ) -> (Subbuffer<[MeshVertex]>, Subbuffer<[u32]>) {
+        let allocation_info = AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        };
+        let vertex_buffer = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            allocation_info.clone(),
+            mesh.vertices.iter().copied(),
+        ).expect("Failed to create vertex buffer");
+        let index_buffer = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::INDEX_BUFFER,
+                ..Default::default()
+            },
+            allocation_info,
+            mesh.indices.iter().copied(),
+        ).expect("Failed to create index buffer");
+        (vertex_buffer, index_buffer)
+    }
+
+    // COMMAND BUFFER
+
+    fn record_command_buffer(&self, image_index: usize) -> Arc<PrimaryAutoCommandBuffer> {
+        let image_extent = self.swap_chain.image_extent();
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &self.command_buffer_allocator,
+            self.graphics_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        ).expect("Failed to create command buffer builder");
+        let render_pass_begin_info = RenderPassBeginInfo {
+            clear_values: vec![
+                Some([0.0, 0.0, 0.0, 1.0].into()),
+                Some(1.0.into()),
+            ],
+            ..RenderPassBeginInfo::framebuffer(self.framebuffers[image_index].clone())
+        };
+        let model = self.model_matrix();
+        let push_constants = PushConstants {
+            mvp: self.mvp_matrix(&model, image_extent),
+            model,
+        };
+        builder
+            .begin_render_pass(
+                render_pass_begin_info,
+                SubpassBeginInfo {
+                    contents: SubpassContents::Inline,
+                    ..Default::default()
+                },
+            )
+            .expect("Failed to begin render pass")
+            .set_viewport(0, [Viewport {
+                extent: [image_extent[0] as f32, image_extent[1] as f32],
+                ..Default::default()
+            }].into_iter().collect())
+            .expect("Failed to set viewport")
+            .set_scissor(0, [Scissor {
+                extent: image_extent,
+                ..Default::default()
+            }].into_iter().collect())
+            .expect("Failed to set scissor")
+            .bind_vertex_buffers(0, self.vertex_buffer.clone())
+            .expect("Failed to bind vertex buffer")
+            .bind_index_buffer(self.index_buffer.clone())
+            .expect("Failed to bind index buffer");
+        for draw in &self.draws {
+            builder
+                .bind_pipeline_graphics(draw.pipeline.clone())
+                .expect("Failed to bind graphics pipeline")
+                .push_constants(draw.pipeline.layout().clone(), 0, push_constants)
+                .expect("Failed to push constants")
+                .draw_indexed(draw.indices.len() as u32, 1, draw.indices.start, 0, 0)
+                .expect("Failed to record draw command");
+        }
+        builder
+            .end_render_pass(SubpassEndInfo::default())
+            .expect("Failed to end render pass");
+        builder.build()
+            .expect("Failed to build command buffer")
+    }
+
+    // TRANSFORMATIONS
+
+    fn normalization_matrix(mesh: &Mesh) -> transform::Matrix4 {
+        let [x, y, z] = mesh.center;
+        let scale = if mesh.size > 0.0 { 2.0 / mesh.size } else { 1.0 };
+        transform::multiply(
+            &transform::scale(scale),
+            &transform::translation(-x, -y, -z),
+        )
+    }
+
+    fn model_matrix(&self) -> transform::Matrix4 {
+        let angle = self.start_time.elapsed().as_secs_f32() * ROTATION_SPEED;
+        transform::multiply(&transform::rotation_y(angle), &self.normalization)
+    }
+
+    fn mvp_matrix(&self, model: &transform::Matrix4, image_extent: [u32; 2]) -> transform::Matrix4 {
+        let aspect = image_extent[0] as f32 / image_extent[1] as f32;
+        let view = transform::translation(0.0, 0.0, -CAMERA_DISTANCE);
+        let projection = transform::perspective(FIELD_OF_VIEW, aspect, 0.1, 100.0);
+        transform::multiply(&projection, &transform::multiply(&view, model))
+    }
+
     // VALIDATION LAYERS
 
     fn check_validation_layers(library: &Arc<VulkanLibrary>) -> bool {
@@ -0,0 +1,99 @@
+use std::ops::Range;
+use vulkano::buffer::BufferContents;
+use vulkano::pipeline::graphics::vertex_input::Vertex;
+use scop::object::{IndexedMesh, Material};
+
+const BASE_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+
+#[derive(BufferContents, Vertex, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MeshVertex {
+    #[format(R32G32B32_SFLOAT)]
+    pub position: [f32; 3],
+    #[format(R32G32B32_SFLOAT)]
+    pub normal: [f32; 3],
+    #[format(R32G32B32_SFLOAT)]
+    pub color: [f32; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    Triangles,
+    Lines,
+    Points,
+}
+
+/// Slice of [`Mesh::indices`] drawn with a single topology.
+#[derive(Debug, Clone)]
+pub struct DrawRange {
+    pub topology: Topology,
+    pub indices: Range<u32>,
+}
+
+pub struct Mesh {
+    pub vertices: Vec<MeshVertex>,
+    /// Triangle, line and point indices, one after the other.
+    pub indices: Vec<u32>,
+    pub draws: Vec<DrawRange>,
+    pub center: [f32; 3],
+    pub size: f32,
+}
+
+impl Mesh {
+    pub fn new(indexed_mesh: &IndexedMesh, materials: &[Material]) -> Self {
+        let vertices: Vec<MeshVertex> = indexed_mesh.vertices
+            .iter()
+            .map(|vertex| MeshVertex {
+                position: vertex.position,
+                normal: vertex.normal,
+                color: vertex.color
+                    .or_else(|| vertex.material.and_then(|index| materials[index].diffuse))
+                    .unwrap_or(BASE_COLOR),
+            })
+            .collect();
+        let mut indices = vec![];
+        let mut draws = vec![];
+        for (topology, list) in [
+            (Topology::Triangles, &indexed_mesh.indices),
+            (Topology::Lines, &indexed_mesh.line_indices),
+            (Topology::Points, &indexed_mesh.point_indices),
+        ] {
+            if !list.is_empty() {
+                let start = indices.len() as u32;
+                indices.extend_from_slice(list);
+                draws.push(DrawRange {
+                    topology,
+                    indices: start..indices.len() as u32,
+                });
+            }
+        }
+        let (center, size) = Self::bounds(&vertices);
+        Self {
+            vertices,
+            indices,
+            draws,
+            center,
+            size,
+        }
+    }
+
+    fn bounds(vertices: &[MeshVertex]) -> ([f32; 3], f32) {
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for vertex in vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex.position[axis]);
+                max[axis] = max[axis].max(vertex.position[axis]);
+            }
+        }
+        let center = [
+            (min[0] + max[0]) / 2.0,
+            (min[1] + max[1]) / 2.0,
+            (min[2] + max[2]) / 2.0,
+        ];
+        let size = (0..3)
+            .map(|axis| max[axis] - min[axis])
+            .fold(0.0, f32::max);
+        (center, size)
+    }
+}
@@ -0,0 +1,57 @@
+/// Column-major 4x4 matrix, laid out the way GLSL expects a `mat4`.
+pub type Matrix4 = [[f32; 4]; 4];
+
+pub fn identity() -> Matrix4 {
+    [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+pub fn multiply(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+    let mut res = [[0.0; 4]; 4];
+    for col in 0..4 {
+        for row in 0..4 {
+            res[col][row] = (0..4).map(|k| a[k][row] * b[col][k]).sum();
+        }
+    }
+    res
+}
+
+pub fn translation(x: f32, y: f32, z: f32) -> Matrix4 {
+    let mut res = identity();
+    res[3] = [x, y, z, 1.0];
+    res
+}
+
+pub fn scale(factor: f32) -> Matrix4 {
+    let mut res = identity();
+    for (i, col) in res.iter_mut().enumerate().take(3) {
+        col[i] = factor;
+    }
+    res
+}
+
+pub fn rotation_y(angle: f32) -> Matrix4 {
+    let (sin, cos) = angle.sin_cos();
+    [
+        [cos, 0.0, -sin, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [sin, 0.0, cos, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Right-handed perspective projection targeting Vulkan's clip space
+/// (y pointing down, depth in `0..1`).
+pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix4 {
+    let f = 1.0 / (fov_y / 2.0).tan();
+    [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, -f, 0.0, 0.0],
+        [0.0, 0.0, far / (near - far), -1.0],
+        [0.0, 0.0, near * far / (near - far), 0.0],
+    ]
+}
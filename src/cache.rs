@@ -0,0 +1,312 @@
+mod codec;
+mod model;
+mod validation;
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use memmap2::Mmap;
+
+use crate::cache::codec::{Decode, Decoder, Encode, Encoder};
+use crate::error::CacheError;
+use crate::object::Object;
+use crate::parser::{ParseMode, ParseOptions};
+
+const MAGIC: [u8; 8] = *b"SCOPMESH";
+/// Version of the format, raised whenever the encoding of the model changes.
+pub const VERSION: u32 = 2;
+pub const EXTENSION: &str = "scache";
+/// Magic, version, payload length and payload checksum.
+const PREAMBLE_LENGTH: usize = 8 + 4 + 8 + 8;
+const CHECKSUM_PRIME: u64 = 0x9E37_79B1_85EB_CA87;
+const CHECKSUM_SEEDS: [u64; 4] = [
+    0x243F_6A88_85A3_08D3,
+    0x1319_8A2E_0370_7344,
+    0xA409_3822_299F_31D0,
+    0x082E_FA98_EC4E_6C89,
+];
+
+/// Where the cache of a source is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheLocation {
+    /// `model.obj.scache` next to `model.obj`.
+    NextToSource,
+    /// A file of the directory named after the source and a hash of its
+    /// absolute path, so that sources sharing a name do not collide.
+    Directory(PathBuf),
+}
+
+/// Header of the payload: what the cached object was built from.
+struct Header {
+    /// Mode of the parse, a lenient one having possibly skipped statements
+    /// that a strict one rejects.
+    mode: ParseMode,
+    /// Resolution the free-form elements were tessellated at.
+    resolution: usize,
+    /// The source first, then its material libraries.
+    dependencies: Vec<Dependency>,
+}
+
+/// File the cached object was built from, as it was when the cache was written.
+struct Dependency {
+    path: String,
+    size: u64,
+    modified: [u64; 2],
+    checksum: u64,
+}
+
+/// Path of the cache of the source at `path`.
+pub fn cache_path(path: &str, location: &CacheLocation) -> Result<PathBuf, CacheError> {
+    match location {
+        CacheLocation::NextToSource => Ok(PathBuf::from(format!("{path}.{EXTENSION}"))),
+        CacheLocation::Directory(directory) => {
+            let absolute = fs::canonicalize(path)
+                .map_err(|e| CacheError::io(path, e))?;
+            let name = absolute.file_name().unwrap_or_default().to_string_lossy();
+            let hash = checksum(absolute.to_string_lossy().as_bytes());
+            Ok(directory.join(format!("{name}-{hash:016x}.{EXTENSION}")))
+        }
+    }
+}
+
+/// Loads the object cached for the source at `path`, which must have been
+/// stored with the same parse mode and tessellation resolution. The cache is memory-mapped,
+/// checked against its checksum and against the size, modification time and
+/// content of the source and its material libraries, and every index of the
+/// object is validated.
+pub fn load(path: &str, location: &CacheLocation, options: &ParseOptions) -> Result<Object, CacheError> {
+    let cache = cache_path(path, location)?;
+    let name = cache.to_string_lossy();
+    let file = File::open(&cache)
+        .map_err(|e| CacheError::io(&name, e))?;
+    // SAFETY: the map is only read, and its content fully validated before
+    // use. Like any reader of the cache, it relies on other processes not
+    // truncating the file while it is being loaded.
+    let map = unsafe { Mmap::map(&file) }
+        .map_err(|e| CacheError::io(&name, e))?;
+    decode(&map, &name, options)
+}
+
+/// Caches `object`, parsed from the source at `path` with `options`. The file
+/// is written next to its final place and renamed, so that readers never see
+/// it partially written.
+pub fn store(object: &Object, path: &str, location: &CacheLocation, options: &ParseOptions) -> Result<(), CacheError> {
+    let cache = cache_path(path, location)?;
+    let name = cache.to_string_lossy();
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    let mut dependencies = vec![Dependency::new(Path::new(path))?];
+    for library in &object.material_libraries {
+        dependencies.push(Dependency::new(&directory.join(library))?);
+    }
+    let header = Header {
+        mode: options.mode,
+        resolution: options.resolution,
+        dependencies,
+    };
+
+    let mut encoder = Encoder::new();
+    encoder.bytes.resize(PREAMBLE_LENGTH, 0);
+    encoder.put(&header);
+    encoder.put(object);
+    let mut preamble = Encoder::new();
+    let payload = &encoder.bytes[PREAMBLE_LENGTH..];
+    preamble.put(&MAGIC);
+    preamble.put(&VERSION);
+    preamble.put(&payload.len());
+    preamble.put(&checksum(payload));
+    encoder.bytes[..PREAMBLE_LENGTH].copy_from_slice(&preamble.bytes);
+
+    if let Some(parent) = cache.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| CacheError::io(&name, e))?;
+    }
+    let temporary = cache.with_extension(format!("{EXTENSION}.tmp"));
+    fs::write(&temporary, &encoder.bytes)
+        .and_then(|()| fs::rename(&temporary, &cache))
+        .map_err(|e| CacheError::io(&name, e))
+}
+
+fn decode(bytes: &[u8], name: &str, options: &ParseOptions) -> Result<Object, CacheError> {
+    let corrupt = |message| CacheError::corrupt(name, message);
+    let mut preamble = Decoder::new(bytes);
+    if preamble.get::<[u8; 8]>().map_err(corrupt)? != MAGIC {
+        return Err(CacheError::corrupt(name, "not a mesh cache"));
+    }
+    let version = preamble.get::<u32>().map_err(corrupt)?;
+    if version != VERSION {
+        return Err(CacheError::stale(name, format!("written with version {version} of the format")));
+    }
+    let length = preamble.get::<usize>().map_err(corrupt)?;
+    let expected = preamble.get::<u64>().map_err(corrupt)?;
+    let payload = &bytes[PREAMBLE_LENGTH..];
+    if payload.len() != length {
+        return Err(CacheError::corrupt(name, format!("expected {length} bytes, found {}", payload.len())));
+    }
+    if checksum(payload) != expected {
+        return Err(CacheError::corrupt(name, "checksum mismatch"));
+    }
+
+    let mut decoder = Decoder::new(payload);
+    let header = decoder.get::<Header>().map_err(corrupt)?;
+    if header.mode != options.mode {
+        let mode = match header.mode {
+            ParseMode::Strict => "strict",
+            ParseMode::Lenient => "lenient",
+        };
+        return Err(CacheError::stale(name, format!("parsed in {mode} mode")));
+    }
+    if header.resolution != options.resolution {
+        return Err(CacheError::stale(name, format!("tessellated at resolution {}", header.resolution)));
+    }
+    for dependency in &header.dependencies {
+        if let Some(reason) = dependency.change() {
+            return Err(CacheError::stale(name, reason));
+        }
+    }
+    let object = decoder.get::<Object>().map_err(corrupt)?;
+    if !decoder.is_empty() {
+        return Err(CacheError::corrupt(name, "trailing data"));
+    }
+    validation::validate(&object).map_err(corrupt)?;
+    Ok(object)
+}
+
+impl Dependency {
+    fn new(path: &Path) -> Result<Self, CacheError> {
+        let name = path.to_string_lossy();
+        let absolute = fs::canonicalize(path)
+            .map_err(|e| CacheError::io(&name, e))?;
+        let (size, modified) = metadata(&absolute)
+            .map_err(|e| CacheError::io(&name, e))?;
+        Ok(Dependency {
+            path: absolute.to_string_lossy().into_owned(),
+            size,
+            modified,
+            checksum: file_checksum(&absolute)
+                .map_err(|e| CacheError::io(&name, e))?,
+        })
+    }
+
+    /// How the file changed since the cache was written, if it did. The
+    /// content is only hashed when the metadata matches.
+    fn change(&self) -> Option<String> {
+        let path = Path::new(&self.path);
+        match metadata(path) {
+            Err(e) => Some(format!("'{}' cannot be read: {e}", self.path)),
+            Ok((size, _)) if size != self.size => Some(format!("'{}' changed size", self.path)),
+            Ok((_, modified)) if modified != self.modified => Some(format!("'{}' was modified", self.path)),
+            Ok(_) => match file_checksum(path) {
+                Err(e) => Some(format!("'{}' cannot be read: {e}", self.path)),
+                Ok(checksum) if checksum != self.checksum => Some(format!("'{}' changed content", self.path)),
+                Ok(_) => None,
+            },
+        }
+    }
+}
+
+impl Encode for Header {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put(&self.mode);
+        encoder.put(&self.resolution);
+        encoder.put(&self.dependencies);
+    }
+}
+
+impl Decode for Header {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        Ok(Header {
+            mode: decoder.get()?,
+            resolution: decoder.get()?,
+            dependencies: decoder.get()?,
+        })
+    }
+}
+
+impl Encode for ParseMode {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            ParseMode::Strict => 0,
+            ParseMode::Lenient => 1,
+        };
+        encoder.put(&tag);
+    }
+}
+
+impl Decode for ParseMode {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        match decoder.get::<u8>()? {
+            0 => Ok(ParseMode::Strict),
+            1 => Ok(ParseMode::Lenient),
+            tag => Err(format!("Invalid parse mode {tag}")),
+        }
+    }
+}
+
+impl Encode for Dependency {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put(&self.path);
+        encoder.put(&self.size);
+        encoder.put(&self.modified);
+        encoder.put(&self.checksum);
+    }
+}
+
+impl Decode for Dependency {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        Ok(Dependency {
+            path: decoder.get()?,
+            size: decoder.get()?,
+            modified: decoder.get()?,
+            checksum: decoder.get()?,
+        })
+    }
+}
+
+/// Size and modification time, as seconds and nanoseconds since the epoch.
+fn metadata(path: &Path) -> io::Result<(u64, [u64; 2])> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok((metadata.len(), [modified.as_secs(), modified.subsec_nanos().into()]))
+}
+
+fn file_checksum(path: &Path) -> io::Result<u64> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(checksum(&[]));
+    }
+    // SAFETY: see `load`.
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(checksum(&map))
+}
+
+/// Hash of `bytes` mixing four independent lanes of 64-bit words, which keeps
+/// it fast enough to run over a whole source on every load. It detects
+/// changes, not tampering.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    let (words, rest) = bytes.as_chunks::<8>();
+    let (blocks, tail) = words.as_chunks::<4>();
+    let mut lanes = CHECKSUM_SEEDS;
+    for block in blocks {
+        for (lane, word) in lanes.iter_mut().zip(block) {
+            *lane = mix(*lane, u64::from_le_bytes(*word));
+        }
+    }
+    for word in tail {
+        lanes[0] = mix(lanes[0], u64::from_le_bytes(*word));
+    }
+    let mut last = [0; 8];
+    last[..rest.len()].copy_from_slice(rest);
+    lanes[1] = mix(lanes[1], u64::from_le_bytes(last));
+    let hash = lanes.iter().fold(bytes.len() as u64, |hash, &lane| mix(hash, lane));
+    let hash = (hash ^ (hash >> 33)).wrapping_mul(CHECKSUM_PRIME);
+    hash ^ (hash >> 29)
+}
+
+fn mix(lane: u64, word: u64) -> u64 {
+    let lane = (lane ^ word).wrapping_mul(CHECKSUM_PRIME);
+    lane ^ (lane >> 32)
+}
@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+/// Little-endian writer of the cache payload.
+pub struct Encoder {
+    pub bytes: Vec<u8>,
+}
+
+/// Reader of the cache payload, failing on anything that would read past its
+/// end or build an invalid value.
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+pub trait Encode {
+    fn encode(&self, encoder: &mut Encoder);
+}
+
+pub trait Decode: Sized {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String>;
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self { bytes: vec![] }
+    }
+
+    pub fn put(&mut self, value: &impl Encode) {
+        value.encode(self);
+    }
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn get<T: Decode>(&mut self) -> Result<T, String> {
+        T::decode(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let (head, rest) = self.bytes.split_first_chunk::<N>()
+            .ok_or("Unexpected end of data")?;
+        self.bytes = rest;
+        Ok(*head)
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.bytes.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    /// Reads a length, which cannot exceed the bytes left since every element
+    /// takes at least one.
+    fn len(&mut self) -> Result<usize, String> {
+        let len = self.get::<usize>()?;
+        if len > self.bytes.len() {
+            return Err(format!("Length {len} exceeds the data left"));
+        }
+        Ok(len)
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! number_codec {
+    ($($number:ty),*) => {$(
+        impl Encode for $number {
+            fn encode(&self, encoder: &mut Encoder) {
+                encoder.bytes.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+
+        impl Decode for $number {
+            fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+                decoder.take().map(<$number>::from_le_bytes)
+            }
+        }
+    )*};
+}
+
+number_codec!(u8, u32, u64, f32);
+
+impl Encode for usize {
+    fn encode(&self, encoder: &mut Encoder) {
+        (*self as u64).encode(encoder);
+    }
+}
+
+impl Decode for usize {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        let value = decoder.get::<u64>()?;
+        usize::try_from(value).map_err(|e| e.to_string())
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, encoder: &mut Encoder) {
+        (*self as u8).encode(encoder);
+    }
+}
+
+impl Decode for bool {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        match decoder.get::<u8>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(format!("Invalid boolean {value}")),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            Some(value) => {
+                encoder.put(&true);
+                encoder.put(value);
+            }
+            None => encoder.put(&false),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        match decoder.get::<bool>()? {
+            true => decoder.get().map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put(&self.len());
+        self.iter().for_each(|value| encoder.put(value));
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_slice().encode(encoder);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        let len = decoder.len()?;
+        (0..len).map(|_| decoder.get()).collect()
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.iter().for_each(|value| encoder.put(value));
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        let values = (0..N).map(|_| decoder.get()).collect::<Result<Vec<T>, _>>()?;
+        values.try_into().map_err(|_| unreachable!("{N} values were decoded"))
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put(&self.len());
+        encoder.bytes.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_str().encode(encoder);
+    }
+}
+
+impl Decode for String {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        let len = decoder.len()?;
+        let bytes = decoder.take_slice(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+    }
+}
+
+impl Encode for Range<usize> {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put(&self.start);
+        encoder.put(&self.end);
+    }
+}
+
+impl Decode for Range<usize> {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        Ok(decoder.get()?..decoder.get()?)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::cache::codec::{Decode, Decoder, Encode, Encoder};
+use crate::object::{
+    Corner, Curve, Curve2, CurveType, Face, FreeFormAttributes, ImageChannel, Line, Material,
+    NamedRange, Object, Point, Surface, TextureMap, TrimCurve, Vertex, VertexNormal,
+    VertexParameter, VertexTexture,
+};
+
+/// Encodes the fields of a struct in the order given, decoding them back into
+/// a struct literal so that a field missing from the list does not compile.
+macro_rules! struct_codec {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {$(
+        impl Encode for $name {
+            fn encode(&self, encoder: &mut Encoder) {
+                $(encoder.put(&self.$field);)*
+            }
+        }
+
+        impl Decode for $name {
+            fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+                Ok($name {
+                    $($field: decoder.get()?,)*
+                })
+            }
+        }
+    )*};
+}
+
+struct_codec! {
+    Vertex { x, y, z, w, color }
+    VertexNormal { i, j, k }
+    VertexTexture { u, v, w }
+    VertexParameter { u, v, w }
+    Corner { vertex, texture, normal }
+    Face { corners, smoothing_group, material }
+    Line { corners, material }
+    Point { vertices, material }
+    NamedRange { name, faces }
+    TrimCurve { range, curve }
+    Curve { range, vertices, parameters, attributes, material }
+    Curve2 { vertices_parameter, parameters, attributes }
+    FreeFormAttributes { curve_type, rational, degree, basis_matrices, step }
+    Surface { range, corners, parameters, trims, holes, attributes, smoothing_group, material }
+    TextureMap {
+        file, blend_u, blend_v, bump_multiplier, boost, color_correction, clamp, channel,
+        range, offset, scale, turbulence, resolution, projection,
+    }
+    Material {
+        name, ambient, diffuse, specular, emissive, transmission_filter, specular_exponent,
+        optical_density, dissolve, illumination, roughness, metallic, sheen,
+        clearcoat_thickness, clearcoat_roughness, anisotropy, anisotropy_rotation,
+        ambient_map, diffuse_map, specular_map, specular_exponent_map, dissolve_map,
+        emissive_map, bump_map, displacement_map, decal_map, reflection_maps, roughness_map,
+        metallic_map, normal_map,
+    }
+    Object {
+        vertices, vertices_normal, vertices_texture, vertices_parameter, faces, lines, points,
+        curves, curves2, surfaces, objects, groups, material_libraries, materials,
+    }
+}
+
+impl Encode for ImageChannel {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            ImageChannel::Red => 0,
+            ImageChannel::Green => 1,
+            ImageChannel::Blue => 2,
+            ImageChannel::Matte => 3,
+            ImageChannel::Luminance => 4,
+            ImageChannel::Depth => 5,
+        };
+        encoder.put(&tag);
+    }
+}
+
+impl Decode for ImageChannel {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        match decoder.get::<u8>()? {
+            0 => Ok(ImageChannel::Red),
+            1 => Ok(ImageChannel::Green),
+            2 => Ok(ImageChannel::Blue),
+            3 => Ok(ImageChannel::Matte),
+            4 => Ok(ImageChannel::Luminance),
+            5 => Ok(ImageChannel::Depth),
+            tag => Err(format!("Invalid image channel {tag}")),
+        }
+    }
+}
+
+impl Encode for CurveType {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            CurveType::BasisMatrix => 0,
+            CurveType::Bezier => 1,
+            CurveType::BSpline => 2,
+            CurveType::Cardinal => 3,
+            CurveType::Taylor => 4,
+        };
+        encoder.put(&tag);
+    }
+}
+
+impl Decode for CurveType {
+    fn decode(decoder: &mut Decoder) -> Result<Self, String> {
+        match decoder.get::<u8>()? {
+            0 => Ok(CurveType::BasisMatrix),
+            1 => Ok(CurveType::Bezier),
+            2 => Ok(CurveType::BSpline),
+            3 => Ok(CurveType::Cardinal),
+            4 => Ok(CurveType::Taylor),
+            tag => Err(format!("Invalid curve type {tag}")),
+        }
+    }
+}
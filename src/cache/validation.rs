@@ -0,0 +1,67 @@
+use crate::object::{Corner, Object};
+
+const FACE_MIN_CORNERS: usize = 3;
+
+/// Checks that every index of `object` points inside the list it refers to,
+/// so that a damaged or forged cache cannot make the rest of the program
+/// index out of bounds.
+pub fn validate(object: &Object) -> Result<(), String> {
+    let materials = object.materials.len();
+    for face in &object.faces {
+        if face.corners.len() < FACE_MIN_CORNERS {
+            return Err(format!("Face with {} corners", face.corners.len()));
+        }
+        corners_check(object, &face.corners)?;
+        index_check("material", face.material, materials)?;
+    }
+    for line in &object.lines {
+        corners_check(object, &line.corners)?;
+        index_check("material", line.material, materials)?;
+    }
+    for point in &object.points {
+        for &vertex in &point.vertices {
+            index_check("vertex", Some(vertex), object.vertices.len())?;
+        }
+        index_check("material", point.material, materials)?;
+    }
+    for range in object.objects.iter().chain(&object.groups) {
+        if range.faces.start > range.faces.end || range.faces.end > object.faces.len() {
+            return Err(format!("Invalid face range {:?} of '{}'", range.faces, range.name));
+        }
+    }
+    for curve in &object.curves {
+        for &vertex in &curve.vertices {
+            index_check("vertex", Some(vertex), object.vertices.len())?;
+        }
+        index_check("material", curve.material, materials)?;
+    }
+    for curve in &object.curves2 {
+        for &vertex in &curve.vertices_parameter {
+            index_check("parameter vertex", Some(vertex), object.vertices_parameter.len())?;
+        }
+    }
+    for surface in &object.surfaces {
+        corners_check(object, &surface.corners)?;
+        index_check("material", surface.material, materials)?;
+        for trim in surface.trims.iter().chain(&surface.holes).flatten() {
+            index_check("trimming curve", Some(trim.curve), object.curves2.len())?;
+        }
+    }
+    Ok(())
+}
+
+fn corners_check(object: &Object, corners: &[Corner]) -> Result<(), String> {
+    for corner in corners {
+        index_check("vertex", Some(corner.vertex), object.vertices.len())?;
+        index_check("texture coordinate", corner.texture, object.vertices_texture.len())?;
+        index_check("normal", corner.normal, object.vertices_normal.len())?;
+    }
+    Ok(())
+}
+
+fn index_check(kind: &str, index: Option<usize>, len: usize) -> Result<(), String> {
+    match index {
+        Some(index) if index >= len => Err(format!("Index {index} of a {kind} is out of bounds ({len})")),
+        _ => Ok(()),
+    }
+}
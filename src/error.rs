@@ -1,43 +1,305 @@
-use std::io;
+use std::{fmt, io};
 
 use crate::parser::Token;
 
-pub fn custom(e: impl ToString) -> io::Error {
-    io::Error::new(
-        io::ErrorKind::Other,
-        e.to_string(),
-    )
+/// Error raised while parsing a single statement, optionally pointing at the
+/// column of the offending token.
+#[derive(Debug)]
+pub struct StatementError {
+    message: String,
+    column: Option<usize>,
 }
 
-pub fn invalid_token(token: &Token) -> io::Error {
-    io::Error::new(
-        io::ErrorKind::InvalidInput,
-        format!("Invalid token '{:?}'", token),
-    )
+impl StatementError {
+    pub fn new(message: impl ToString) -> Self {
+        Self {
+            message: message.to_string(),
+            column: None,
+        }
+    }
+
+    /// Attaches a column to the error, unless a more precise one was already set.
+    pub fn at(mut self, column: usize) -> Self {
+        self.column.get_or_insert(column);
+        self
+    }
+}
+
+impl fmt::Display for StatementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io {
+        path: String,
+        error: io::Error,
+    },
+    Syntax {
+        path: String,
+        line: usize,
+        column: usize,
+        source_line: String,
+        message: String,
+    },
+    /// Error in a binary source, which has no line to point at.
+    Invalid {
+        path: String,
+        message: String,
+    },
+}
+
+impl ParseError {
+    pub fn io(path: &str, error: io::Error) -> Self {
+        Self::Io {
+            path: path.to_string(),
+            error,
+        }
+    }
+
+    /// Locates a statement error in `source_line`, the 1-based `line` of `path`.
+    /// Errors without a column point at the first token of the line.
+    pub fn syntax(path: &str, line: usize, source_line: &str, error: StatementError) -> Self {
+        let column = error.column.unwrap_or_else(|| {
+            source_line.chars().take_while(|c| c.is_whitespace()).count() + 1
+        });
+        Self::Syntax {
+            path: path.to_string(),
+            line,
+            column,
+            source_line: source_line.to_string(),
+            message: error.message,
+        }
+    }
+
+    pub fn invalid(path: &str, message: impl ToString) -> Self {
+        Self::Invalid {
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, severity: &str) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{severity}: {path}: {error}"),
+            Self::Invalid { path, message } => write!(f, "{severity}: {path}: {message}"),
+            Self::Syntax { path, line, column, source_line, message } => {
+                let gutter = " ".repeat(line.to_string().len());
+                let padding: String = source_line.chars()
+                    .take(column - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                writeln!(f, "{severity}: {message}")?;
+                writeln!(f, "{gutter}--> {path}:{line}:{column}")?;
+                writeln!(f, "{gutter} |")?;
+                writeln!(f, "{line} | {source_line}")?;
+                write!(f, "{gutter} | {padding}^")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, "error")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error the lenient parser recovered from by skipping what caused it.
+#[derive(Debug)]
+pub struct ParseWarning(pub ParseError);
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.render(f, "warning")
+    }
+}
+
+/// Reason why a mesh cache could not be used, in which case the source is to
+/// be parsed again.
+#[derive(Debug)]
+pub enum CacheError {
+    Io {
+        path: String,
+        error: io::Error,
+    },
+    /// The cache was written by another version of the format, with other
+    /// options, or before one of its sources changed.
+    Stale {
+        path: String,
+        reason: String,
+    },
+    /// The cache is damaged: truncated, failing its checksum, or describing
+    /// an invalid object.
+    Corrupt {
+        path: String,
+        message: String,
+    },
+}
+
+impl CacheError {
+    pub fn io(path: &str, error: io::Error) -> Self {
+        Self::Io {
+            path: path.to_string(),
+            error,
+        }
+    }
+
+    pub fn stale(path: &str, reason: impl ToString) -> Self {
+        Self::Stale {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn corrupt(path: &str, message: impl ToString) -> Self {
+        Self::Corrupt {
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{path}: {error}"),
+            Self::Stale { path, reason } => write!(f, "{path}: stale cache, {reason}"),
+            Self::Corrupt { path, message } => write!(f, "{path}: corrupt cache, {message}"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+pub fn custom(e: impl ToString) -> StatementError {
+    StatementError::new(e)
+}
+
+pub fn invalid_token(token: &Token) -> StatementError {
+    StatementError::new(format!("Invalid token '{}'", token))
+}
+
+pub fn unexpected_token(token: &Token) -> StatementError {
+    StatementError::new(format!("Found unexpected token '{}'", token))
+}
+
+pub fn invalid_statement() -> StatementError {
+    StatementError::new("Invalid statement")
+}
+
+pub fn non_value_token() -> StatementError {
+    StatementError::new("Non value token")
+}
+
+pub fn invalid_argument_length(expected: impl fmt::Display, received: usize) -> StatementError {
+    StatementError::new(format!("Expected {expected}, received {received}"))
+}
+
+pub fn index_out_of_bound(index: isize, len: usize) -> StatementError {
+    StatementError::new(format!("Trying to access an index of {index} when array is of len {len}"))
+}
+
+pub fn missing_material() -> StatementError {
+    StatementError::new("Material property set before any 'newmtl' statement")
+}
+
+pub fn missing_file_name() -> StatementError {
+    StatementError::new("Expected a file name after the options")
+}
+
+pub fn missing_option_argument() -> StatementError {
+    StatementError::new("Expected an argument after the option")
+}
+
+pub fn unknown_option(option: &str) -> StatementError {
+    StatementError::new(format!("Unknown option '{option}'"))
+}
+
+pub fn invalid_utf8() -> StatementError {
+    StatementError::new("Invalid UTF-8 sequence")
+}
+
+pub fn null_index() -> StatementError {
+    StatementError::new("Index 0 is invalid, indices start at 1 or count back from -1")
+}
+
+pub fn missing_curve_type() -> StatementError {
+    StatementError::new("Free-form element declared before any 'cstype' statement")
+}
+
+pub fn misplaced_statement(token: &Token) -> StatementError {
+    StatementError::new(format!("'{token}' statement outside of the free-form element it applies to"))
+}
+
+pub fn unterminated_free_form_element() -> StatementError {
+    StatementError::new("Free-form element without an 'end' before the next one or the end of the file")
+}
+
+pub fn missing_parameter_v() -> StatementError {
+    StatementError::new("Trimming curve control point without a v parameter")
+}
+
+pub fn invalid_degree() -> StatementError {
+    StatementError::new("Invalid degree for the curve type")
+}
+
+pub fn invalid_step() -> StatementError {
+    StatementError::new("Basis matrix elements need a step of at least 1")
+}
+
+pub fn invalid_basis_matrix(expected: usize, received: usize) -> StatementError {
+    StatementError::new(format!("Expected a basis matrix of {expected} values, received {received}"))
+}
+
+pub fn invalid_parameter_count(expected: usize, received: usize) -> StatementError {
+    StatementError::new(format!("Expected at least {expected} global parameters, received {received}"))
+}
+
+pub fn invalid_parameters() -> StatementError {
+    StatementError::new("Global parameters must be finite and non-decreasing over a non-empty range")
+}
+
+pub fn invalid_control_point_count(expected: usize, received: usize) -> StatementError {
+    StatementError::new(format!("Expected {expected} control points, received {received}"))
+}
+
+pub fn invalid_facet(vertices: usize) -> StatementError {
+    StatementError::new(format!("Facet with {vertices} vertices"))
+}
+
+pub fn unterminated_solid() -> StatementError {
+    StatementError::new("Expected 'endsolid' after the last facet")
 }
 
-pub fn unexpected_token(token: &Token) -> io::Error {
-    io::Error::new(io::ErrorKind::InvalidInput, format!("Found unexpected token '{:?}'", token))
+pub fn missing_ply_magic() -> StatementError {
+    StatementError::new("Expected 'ply' on the first line")
 }
 
-pub fn invalid_statement() -> io::Error {
-    io::Error::new(io::ErrorKind::InvalidInput, "Invalid statement")
+pub fn unsupported_version(token: &Token) -> StatementError {
+    StatementError::new(format!("Unsupported version '{}'", token))
 }
 
-pub fn non_value_token() -> io::Error {
-    io::Error::new(io::ErrorKind::InvalidInput, "Non value token")
+pub fn non_integer_index(index: f64) -> StatementError {
+    StatementError::new(format!("Vertex index {index} is not an integer"))
 }
 
-pub fn invalid_argument_length(expected: impl std::fmt::Display, received: usize)  -> io::Error {
-    io::Error::new(
-        io::ErrorKind::InvalidInput,
-        format!("Expected {expected}, received {received}")
-    )
+pub fn misplaced_property() -> StatementError {
+    StatementError::new("Property declared before any element")
 }
 
-pub fn index_out_of_bound(index: usize, len: usize) -> io::Error {
-    io::Error::new(
-        io::ErrorKind::InvalidInput,
-        format!("Trying to access an index of {index} when array is of len {len}")
-    )
-}
\ No newline at end of file
+pub fn unknown_type(token: &Token) -> StatementError {
+    StatementError::new(format!("Unknown type '{}'", token))
+}
+
+pub fn missing_value() -> StatementError {
+    StatementError::new("Expected another value for the element")
+}
+
+pub fn invalid_face(corners: usize) -> StatementError {
+    StatementError::new(format!("Face with {corners} corners"))
+}
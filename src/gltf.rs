@@ -0,0 +1,236 @@
+mod material;
+
+use std::io::{self, Write};
+use ahash::{HashMap, HashMapExt};
+use serde_json::{json, Map, Value};
+use crate::object::vector;
+use crate::object::{Grouping, IndexedMesh, Object};
+use material::Textures;
+
+const VERSION: &str = "2.0";
+const GENERATOR: &str = "scop";
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+const TRIANGLES: u32 = 4;
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_VERSION: u32 = 2;
+const GLB_HEADER_SIZE: usize = 12;
+const CHUNK_HEADER_SIZE: usize = 8;
+const JSON_CHUNK: u32 = 0x4E4F_534A;
+const BIN_CHUNK: u32 = 0x004E_4942;
+/// Alignment of the GLB chunks.
+const CHUNK_ALIGNMENT: usize = 4;
+
+/// Writes `object` as a glTF document to `json` and the buffer holding its
+/// vertices and indices to `buffer`, which the document refers to as
+/// `buffer_uri`, usually the name of a `.bin` file next to it.
+///
+/// The faces make a single mesh whose vertices are the distinct `(v, vt, vn)`
+/// corners of each material, as in [`Object::indexed_mesh`]. Each group, faces outside any group included, gives a triangle
+/// primitive per material, named by its `extras`. Normals, texture
+/// coordinates and colours are written when every corner has some, and the
+/// materials are mapped to the metallic-roughness model, their texture maps
+/// staying separate files. Lines and points are left out.
+pub fn write(object: &Object, json: impl Write, mut buffer: impl Write, buffer_uri: &str) -> io::Result<()> {
+    let (mut document, bytes) = document(object)?;
+    document["buffers"][0]["uri"] = json!(material::uri_encode(buffer_uri));
+    serde_json::to_writer_pretty(json, &document)?;
+    buffer.write_all(&bytes)
+}
+
+/// Writes `object` as a single GLB file embedding the buffer, see [`write`].
+pub fn write_binary(object: &Object, mut writer: impl Write) -> io::Result<()> {
+    let (document, mut bytes) = document(object)?;
+    let mut json = serde_json::to_vec(&document)?;
+    pad(&mut json, b' ');
+    pad(&mut bytes, 0);
+    let length = u32::try_from(GLB_HEADER_SIZE + 2 * CHUNK_HEADER_SIZE + json.len() + bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Mesh too large for a GLB file"))?;
+    writer.write_all(GLB_MAGIC)?;
+    writer.write_all(&GLB_VERSION.to_le_bytes())?;
+    writer.write_all(&length.to_le_bytes())?;
+    for (chunk, kind) in [(&json, JSON_CHUNK), (&bytes, BIN_CHUNK)] {
+        writer.write_all(&(chunk.len() as u32).to_le_bytes())?;
+        writer.write_all(&kind.to_le_bytes())?;
+        writer.write_all(chunk)?;
+    }
+    Ok(())
+}
+
+fn pad(chunk: &mut Vec<u8>, byte: u8) {
+    chunk.resize(chunk.len().next_multiple_of(CHUNK_ALIGNMENT), byte);
+}
+
+/// Buffer under construction, along with its views and accessors.
+struct Buffer {
+    bytes: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl Buffer {
+    /// Adds a vertex attribute of `N` floats, with the bounds the
+    /// specification requires for positions.
+    fn floats<const N: usize>(&mut self, values: &[[f32; N]]) -> usize {
+        let mut min = [f32::INFINITY; N];
+        let mut max = [f32::NEG_INFINITY; N];
+        for value in values {
+            for i in 0..N {
+                min[i] = min[i].min(value[i]);
+                max[i] = max[i].max(value[i]);
+            }
+        }
+        let view = self.view(values.iter().flatten().flat_map(|value| value.to_le_bytes()), ARRAY_BUFFER);
+        self.accessor(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": format!("VEC{N}"),
+            "min": min.as_slice(),
+            "max": max.as_slice(),
+        }))
+    }
+
+    fn indices(&mut self, indices: &[u32]) -> usize {
+        let view = self.view(indices.iter().flat_map(|index| index.to_le_bytes()), ELEMENT_ARRAY_BUFFER);
+        self.accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }))
+    }
+
+    /// Every value being 4 bytes long, views stay aligned without padding.
+    fn view(&mut self, bytes: impl Iterator<Item = u8>, target: u32) -> usize {
+        let offset = self.bytes.len();
+        self.bytes.extend(bytes);
+        self.views.push(json!({
+            "buffer": 0,
+            "byteOffset": offset,
+            "byteLength": self.bytes.len() - offset,
+            "target": target,
+        }));
+        self.views.len() - 1
+    }
+
+    fn accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+/// Faces drawn with the same material within a group.
+struct Primitive<'a> {
+    group: Option<&'a str>,
+    material: Option<usize>,
+    indices: Vec<u32>,
+}
+
+/// Builds the document, whose single buffer has no URI yet, and the bytes of
+/// that buffer.
+fn document(object: &Object) -> io::Result<(Value, Vec<u8>)> {
+    if object.faces.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "No face to write as a glTF mesh"));
+    }
+    let mesh = object.indexed_mesh();
+    let vertices = mesh.triangle_vertices();
+    let primitives = primitives(object, &mesh);
+    let mut buffer = Buffer {
+        bytes: vec![],
+        views: vec![],
+        accessors: vec![],
+    };
+
+    let mut attributes = Map::new();
+    let positions: Vec<_> = vertices.iter().map(|vertex| vertex.position).collect();
+    attributes.insert("POSITION".into(), json!(buffer.floats(&positions)));
+    let corners = || object.faces.iter().flat_map(|face| &face.corners);
+    if corners().all(|corner| corner.normal.is_some()) {
+        let normals: Vec<_> = vertices.iter().map(|vertex| vector::normalize(vertex.normal)).collect();
+        attributes.insert("NORMAL".into(), json!(buffer.floats(&normals)));
+    }
+    if corners().all(|corner| corner.texture.is_some()) {
+        let texture: Vec<_> = vertices.iter().map(|vertex| [vertex.texture[0], 1.0 - vertex.texture[1]]).collect();
+        attributes.insert("TEXCOORD_0".into(), json!(buffer.floats(&texture)));
+    }
+    if let Some(colors) = vertices.iter()
+        .map(|vertex| vertex.color)
+        .collect::<Option<Vec<_>>>()
+    {
+        attributes.insert("COLOR_0".into(), json!(buffer.floats(&colors)));
+    }
+
+    let primitives: Vec<_> = primitives.iter()
+        .map(|primitive| {
+            let mut value = json!({
+                "attributes": attributes,
+                "indices": buffer.indices(&primitive.indices),
+                "mode": TRIANGLES,
+            });
+            if let Some(material) = primitive.material {
+                value["material"] = json!(material);
+            }
+            if let Some(group) = primitive.group {
+                value["extras"] = json!({ "name": group });
+            }
+            value
+        })
+        .collect();
+    let mut textures = Textures::new();
+    let materials: Vec<_> = object.materials.iter()
+        .map(|m| material::material(m, &mut textures))
+        .collect();
+
+    let mut mesh = json!({ "primitives": primitives });
+    let mut node = json!({ "mesh": 0 });
+    if let Some(range) = object.objects.first() {
+        mesh["name"] = json!(range.name);
+        node["name"] = json!(range.name);
+    }
+    let mut document = json!({
+        "asset": { "version": VERSION, "generator": GENERATOR },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [node],
+        "meshes": [mesh],
+        "buffers": [{ "byteLength": buffer.bytes.len() }],
+        "bufferViews": buffer.views,
+        "accessors": buffer.accessors,
+    });
+    for (key, list) in [("materials", materials), ("textures", textures.textures), ("images", textures.images)] {
+        if !list.is_empty() {
+            document[key] = json!(list);
+        }
+    }
+    Ok((document, buffer.bytes))
+}
+
+/// Splits the triangles of `mesh` into primitives, in the order they first
+/// appear. Faces belonging to several groups go to the first one.
+fn primitives<'a>(object: &'a Object, mesh: &IndexedMesh) -> Vec<Primitive<'a>> {
+    let mut groups = vec![None; object.faces.len()];
+    for sub_mesh in object.sub_meshes(Grouping::Group) {
+        for range in &sub_mesh.ranges {
+            groups[range.clone()].iter_mut()
+                .filter(|group| group.is_none())
+                .for_each(|group| *group = Some(sub_mesh.name));
+        }
+    }
+    let mut primitives: Vec<Primitive> = vec![];
+    let mut known_primitives = HashMap::new();
+    for ((face, range), group) in object.faces.iter().zip(&mesh.face_ranges).zip(groups) {
+        let primitive = *known_primitives.entry((group, face.material)).or_insert_with(|| {
+            primitives.push(Primitive {
+                group,
+                material: face.material,
+                indices: vec![],
+            });
+            primitives.len() - 1
+        });
+        primitives[primitive].indices.extend_from_slice(&mesh.indices[range.clone()]);
+    }
+    primitives
+}
@@ -0,0 +1,91 @@
+use ahash::{HashMap, HashMapExt};
+use serde_json::{json, Value};
+use crate::object::{Material, TextureMap};
+
+/// Images and textures the materials refer to, each file being listed once.
+pub struct Textures {
+    pub images: Vec<Value>,
+    pub textures: Vec<Value>,
+    known: HashMap<String, usize>,
+}
+
+impl Textures {
+    pub fn new() -> Self {
+        Self {
+            images: vec![],
+            textures: vec![],
+            known: HashMap::new(),
+        }
+    }
+
+    /// Texture info pointing at the file of `map`, which stays a separate
+    /// file referred to by its relative URI.
+    fn info(&mut self, map: &TextureMap) -> Value {
+        let index = *self.known.entry(map.file.clone()).or_insert_with(|| {
+            self.images.push(json!({ "uri": uri_encode(&map.file.replace('\\', "/")) }));
+            self.textures.push(json!({ "source": self.images.len() - 1 }));
+            self.textures.len() - 1
+        });
+        json!({ "index": index })
+    }
+}
+
+/// Maps an MTL material to the metallic-roughness model.
+///
+/// `Kd` and `d` give the base colour, `Pm` and `Pr` the metallic and roughness
+/// factors. Materials without `Pm` are dielectric, and those without `Pr`
+/// derive their roughness from `Ns`, the Blinn-Phong exponent, or are fully
+/// rough. `Ke` gives the emissive factor and the `map_Kd`, `map_Ke` and
+/// `norm` maps the matching textures. Every factor is clamped to `[0, 1]`.
+pub fn material(material: &Material, textures: &mut Textures) -> Value {
+    let [r, g, b] = material.diffuse.unwrap_or([1.0; 3]).map(unit);
+    let alpha = unit(material.dissolve.unwrap_or(1.0));
+    let roughness = material.roughness
+        .or(material.specular_exponent.map(|exponent| (2.0 / (exponent.max(0.0) + 2.0)).sqrt()))
+        .unwrap_or(1.0);
+    let mut pbr = json!({
+        "baseColorFactor": [r, g, b, alpha],
+        "metallicFactor": unit(material.metallic.unwrap_or(0.0)),
+        "roughnessFactor": unit(roughness),
+    });
+    if let Some(map) = &material.diffuse_map {
+        pbr["baseColorTexture"] = textures.info(map);
+    }
+    let mut value = json!({
+        "name": material.name,
+        "pbrMetallicRoughness": pbr,
+    });
+    if let Some(emissive) = material.emissive {
+        value["emissiveFactor"] = json!(emissive.map(unit));
+    }
+    if let Some(map) = &material.emissive_map {
+        value["emissiveTexture"] = textures.info(map);
+        if material.emissive.is_none() {
+            value["emissiveFactor"] = json!([1.0, 1.0, 1.0]);
+        }
+    }
+    if let Some(map) = &material.normal_map {
+        value["normalTexture"] = textures.info(map);
+    }
+    if alpha < 1.0 {
+        value["alphaMode"] = json!("BLEND");
+    }
+    value
+}
+
+fn unit(value: f32) -> f32 {
+    value.clamp(0.0, 1.0)
+}
+
+/// Percent-encodes every byte of `path` but the unreserved characters and
+/// the separators, giving a relative URI reference.
+pub fn uri_encode(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
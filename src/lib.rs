@@ -0,0 +1,8 @@
+pub mod cache;
+pub mod error;
+pub mod gltf;
+pub mod object;
+pub mod parser;
+pub mod ply;
+pub mod stl;
+pub mod writer;
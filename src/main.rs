@@ -1,17 +1,203 @@
-mod error;
-mod object;
-mod parser;
 mod application;
 
-use std::io;
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
 use winit::event_loop::{ControlFlow, EventLoop};
+use scop::cache::{self, CacheLocation};
+use scop::error::CacheError;
+use scop::gltf;
+use scop::object::{Face, Grouping, NormalMode, Object};
+use scop::parser::{self, ParseMode, ParseOptions};
+use scop::ply::{self, PlyFormat};
+use scop::stl::{self, StlFormat};
+use scop::writer::{self, WriteOptions};
 
-fn main() -> Result<(), io::Error> {
-    // let obj = parser::parse("resources/42.obj")?;
-    // dbg!(obj);
-    let event_loop = EventLoop::new().unwrap();
+const USAGE: &str = "Usage: scop [--lenient] [--resolution <segments>] [--list-groups] [--cache-dir <dir> | --no-cache] \
+    [--export <file.obj | file.stl | file.ply | file.gltf | file.glb> [--ascii]] <file.obj | file.stl | file.ply>";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut options = ParseOptions {
+        threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        ..ParseOptions::default()
+    };
+    let mut location = Some(CacheLocation::NextToSource);
+    let mut output = None;
+    let mut ascii = false;
+    let mut list_groups = false;
+    let mut args = env::args().skip(1);
+    let path = loop {
+        match args.next().as_deref() {
+            Some("--lenient") => options.mode = ParseMode::Lenient,
+            Some("--resolution") => {
+                options.resolution = args.next()
+                    .and_then(|resolution| resolution.parse().ok())
+                    .ok_or(USAGE)?;
+            }
+            Some("--cache-dir") => {
+                location = Some(CacheLocation::Directory(args.next().map(PathBuf::from).ok_or(USAGE)?));
+            }
+            Some("--no-cache") => location = None,
+            Some("--list-groups") => list_groups = true,
+            Some("--export") => output = Some(args.next().ok_or(USAGE)?),
+            Some("--ascii") => ascii = true,
+            Some(path) => break path.to_string(),
+            None => return Err(USAGE.into()),
+        }
+    };
+    if let Some(output) = output {
+        return export(&path, &output, ascii, &options);
+    }
+    let mut object = match &location {
+        Some(location) => load(&path, location, &options)?,
+        None => parse(&path, &options)?.0,
+    };
+    if object.faces.is_empty() && object.lines.is_empty() && object.points.is_empty() {
+        return Err(format!("'{path}' does not contain any element").into());
+    }
+    if list_groups {
+        print_sub_meshes(&object);
+    }
+    if !object.has_normals() {
+        object.generate_normals(normal_mode(&object));
+    }
+    object.triangulate();
+    let indexed_mesh = object.indexed_mesh();
+    println!(
+        "Loaded '{path}': {} triangles, {} segments, {} points, {} unique vertices",
+        indexed_mesh.triangles(),
+        indexed_mesh.segments(),
+        indexed_mesh.points(),
+        indexed_mesh.unique_vertices(),
+    );
+    let event_loop = EventLoop::new()?;
     event_loop.set_control_flow(ControlFlow::Poll);
-    let mut application = application::Application::new();
-    event_loop.run_app(&mut application).unwrap();
+    let mut application = application::Application::new(&indexed_mesh, &object.materials);
+    event_loop.run_app(&mut application)?;
+    Ok(())
+}
+
+/// Loads the object from its cache, parsing the source and caching it when
+/// the cache is missing or outdated. Objects parsed with warnings are not
+/// cached, so that the warnings show again on the next run.
+fn load(path: &str, location: &CacheLocation, options: &ParseOptions) -> Result<Object, Box<dyn Error>> {
+    match cache::load(path, location, options) {
+        Ok(object) => return Ok(object),
+        Err(CacheError::Io { error, .. }) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(CacheError::Stale { .. }) => {}
+        Err(e) => eprintln!("{e}"),
+    }
+    let (object, warned) = parse(path, options)?;
+    if !warned {
+        if let Err(e) = cache::store(&object, path, location, options) {
+            eprintln!("{e}");
+        }
+    }
+    Ok(object)
+}
+
+/// Parses the source, printing its warnings and telling whether there were any.
+/// STL and PLY files are recognised by their extension.
+fn parse(path: &str, options: &ParseOptions) -> Result<(Object, bool), Box<dyn Error>> {
+    if has_extension(path, "stl") {
+        return Ok((stl::parse(path)?, false));
+    }
+    if has_extension(path, "ply") {
+        return Ok((ply::parse(path)?.object, false));
+    }
+    let parsed = parser::parse_with(path, options)?;
+    for warning in &parsed.warnings {
+        eprintln!("{warning}");
+    }
+    Ok((parsed.object, !parsed.warnings.is_empty()))
+}
+
+/// Converts the source to the format given by the extension of `output`,
+/// without the cache. OBJ files get their materials written to an MTL file
+/// next to them and glTF files their buffer to a BIN file. The extra vertex
+/// attributes of a PLY source are kept when converting to PLY.
+fn export(path: &str, output: &str, ascii: bool, options: &ParseOptions) -> Result<(), Box<dyn Error>> {
+    let (mut object, attributes) = match has_extension(path, "ply") {
+        true => {
+            let ply = ply::parse(path)?;
+            (ply.object, ply.attributes)
+        }
+        false => (parse(path, options)?.0, vec![]),
+    };
+    if !["obj", "stl", "ply", "gltf", "glb"].iter().any(|extension| has_extension(output, extension)) {
+        return Err(USAGE.into());
+    }
+    let create = |path: &Path| File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| format!("{}: {e}", path.display()));
+    let mut file = create(Path::new(output))?;
+    if has_extension(output, "obj") {
+        let options = WriteOptions::default();
+        object.material_libraries.clear();
+        if !object.materials.is_empty() {
+            let library = Path::new(output).with_extension("mtl");
+            let mut library_file = create(&library)?;
+            writer::write_material_library(&object.materials, &mut library_file, &options)?;
+            library_file.flush()?;
+            object.material_libraries.extend(library.file_name().map(|name| name.to_string_lossy().into_owned()));
+        }
+        writer::write(&object, &mut file, &options)?;
+    } else if has_extension(output, "gltf") || has_extension(output, "glb") {
+        if !object.has_normals() {
+            object.generate_normals(normal_mode(&object));
+        }
+        if has_extension(output, "glb") {
+            gltf::write_binary(&object, &mut file)?;
+        } else {
+            let buffer = Path::new(output).with_extension("bin");
+            let mut buffer_file = create(&buffer)?;
+            let uri = buffer.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            gltf::write(&object, &mut file, &mut buffer_file, &uri)?;
+            buffer_file.flush()?;
+        }
+    } else if has_extension(output, "stl") {
+        stl::write(&object, &mut file, if ascii { StlFormat::Ascii } else { StlFormat::Binary })?;
+    } else {
+        ply::write(&object, &attributes, &mut file, if ascii { PlyFormat::Ascii } else { PlyFormat::BinaryLittleEndian })?;
+    }
+    file.flush()?;
+    println!("Exported '{path}' to '{output}'");
     Ok(())
 }
+
+/// Smooths within the smoothing groups of the file, or across every face when
+/// it declares none, rather than leaving it faceted.
+fn normal_mode(object: &Object) -> NormalMode {
+    if object.faces.iter().any(Face::is_smooth) {
+        NormalMode::Smooth
+    } else {
+        NormalMode::SmoothAll
+    }
+}
+
+fn has_extension(path: &str, extension: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+fn print_sub_meshes(object: &Object) {
+    for (grouping, label) in [(Grouping::Object, "Object"), (Grouping::Group, "Group")] {
+        for sub_mesh in object.sub_meshes(grouping) {
+            println!("{label} '{}': {} faces", sub_mesh.name, sub_mesh.faces().count());
+        }
+    }
+}
@@ -1,21 +1,54 @@
 mod face;
+mod free_form;
+mod indexed_mesh;
+mod line;
+mod material;
+mod named_range;
+mod normals;
+mod point;
+pub(crate) mod vector;
 mod vertex;
 mod vertex_normal;
+mod vertex_parameter;
 mod vertex_texture;
 
-use std::rc::Rc;
+use std::mem;
+use crate::error::{self, StatementError};
 
-pub use face::Face;
+pub use face::{Corner, Face, SMOOTHING_GROUP_OFF};
+pub use free_form::{Curve, Curve2, CurveType, FreeFormAttributes, Surface, TrimCurve, U, V};
+pub use indexed_mesh::{IndexedMesh, InterleavedVertex};
+pub use line::Line;
+pub use material::{ImageChannel, Material, TextureMap};
+pub use named_range::{Grouping, NamedRange, SubMesh};
+pub use normals::NormalMode;
+pub use point::Point;
 pub use vertex::Vertex;
 pub use vertex_normal::VertexNormal;
+pub use vertex_parameter::VertexParameter;
 pub use vertex_texture::VertexTexture;
 
-#[derive(Debug)]
+/// Mesh data as laid out in the file: contiguous attribute arrays referenced
+/// by index from the face corners.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Object {
-    pub vertices: Vec<Rc<Vertex>>,
-    pub vertices_normal: Vec<Rc<VertexNormal>>,
-    pub vertices_texture: Vec<Rc<VertexTexture>>,
+    pub vertices: Vec<Vertex>,
+    pub vertices_normal: Vec<VertexNormal>,
+    pub vertices_texture: Vec<VertexTexture>,
+    pub vertices_parameter: Vec<VertexParameter>,
     pub faces: Vec<Face>,
+    pub lines: Vec<Line>,
+    pub points: Vec<Point>,
+    /// Free-form elements, which the parser also tessellates into faces and
+    /// lines.
+    pub curves: Vec<Curve>,
+    pub curves2: Vec<Curve2>,
+    pub surfaces: Vec<Surface>,
+    pub objects: Vec<NamedRange>,
+    pub groups: Vec<NamedRange>,
+    /// Files named by `mtllib` statements, as written in the file.
+    pub material_libraries: Vec<String>,
+    pub materials: Vec<Material>,
 }
 
 impl Object {
@@ -24,23 +57,111 @@ impl Object {
             vertices: vec![],
             vertices_normal: vec![],
             vertices_texture: vec![],
+            vertices_parameter: vec![],
             faces: vec![],
+            lines: vec![],
+            points: vec![],
+            curves: vec![],
+            curves2: vec![],
+            surfaces: vec![],
+            objects: vec![],
+            groups: vec![],
+            material_libraries: vec![],
+            materials: vec![],
         }
     }
 
     pub fn add_vertex(&mut self, vertex: Vertex) {
-        self.vertices.push(Rc::new(vertex));
+        self.vertices.push(vertex);
     }
 
     pub fn add_vertex_normal(&mut self, vertex_normal: VertexNormal) {
-        self.vertices_normal.push(Rc::new(vertex_normal));
+        self.vertices_normal.push(vertex_normal);
     }
 
     pub fn add_vertex_texture(&mut self, vertex_texture: VertexTexture) {
-        self.vertices_texture.push(Rc::new(vertex_texture));
+        self.vertices_texture.push(vertex_texture);
+    }
+
+    pub fn add_vertex_parameter(&mut self, vertex_parameter: VertexParameter) {
+        self.vertices_parameter.push(vertex_parameter);
     }
 
     pub fn add_face(&mut self, face: Face) {
         self.faces.push(face);
+        self.extend_ranges();
+    }
+
+    pub fn add_line(&mut self, line: Line) {
+        self.lines.push(line);
+    }
+
+    pub fn add_point(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    pub fn add_curve(&mut self, curve: Curve) {
+        self.curves.push(curve);
+    }
+
+    pub fn add_curve2(&mut self, curve2: Curve2) {
+        self.curves2.push(curve2);
     }
-}
\ No newline at end of file
+
+    pub fn add_surface(&mut self, surface: Surface) {
+        self.surfaces.push(surface);
+    }
+
+    /// Returns the index of the material called `name`, declaring an empty
+    /// one if the libraries have not defined it yet.
+    pub fn material_index(&mut self, name: &str) -> usize {
+        match self.materials.iter().position(|material| material.name == name) {
+            Some(index) => index,
+            None => {
+                self.materials.push(Material::new(name.to_string()));
+                self.materials.len() - 1
+            }
+        }
+    }
+
+    /// Replaces every polygon by the triangles it is made of.
+    pub fn triangulate(&mut self) {
+        let faces = mem::take(&mut self.faces);
+        let mut offsets = Vec::with_capacity(faces.len() + 1);
+        for face in &faces {
+            offsets.push(self.faces.len());
+            self.faces.extend(face.triangulate(&self.vertices));
+        }
+        offsets.push(self.faces.len());
+        self.remap_ranges(&offsets);
+    }
+}
+
+impl Default for Object {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts a 1-based index into a position in a list of `len` elements,
+/// negative indices being relative to its end (`-1` is the last element).
+fn resolve_index(index: isize, len: usize) -> Result<usize, StatementError> {
+    let position = match index {
+        0 => return Err(error::null_index()),
+        1.. => index.unsigned_abs() - 1,
+        _ => len.checked_sub(index.unsigned_abs())
+            .ok_or_else(|| error::index_out_of_bound(index, len))?,
+    };
+    if position >= len {
+        return Err(error::index_out_of_bound(index, len));
+    }
+    Ok(position)
+}
+
+fn resolve_optional_index(opt: Option<isize>, len: usize) -> Result<Option<usize>, StatementError> {
+    let res = match opt {
+        Some(index) => Some(resolve_index(index, len)?),
+        None => None,
+    };
+    Ok(res)
+}
@@ -1,39 +1,81 @@
-use std::io;
-use std::rc::Rc;
-use crate::error;
+mod triangulation;
 
-use crate::object::{Object, Vertex, VertexNormal, VertexTexture};
+use crate::error::StatementError;
 
-#[derive(Debug)]
+use crate::object::{resolve_index, resolve_optional_index, Object, Vertex};
+
+/// Smoothing group of faces declared after `s off` or `s 0`.
+pub const SMOOTHING_GROUP_OFF: u32 = 0;
+
+/// Indices of the vertex, texture coordinate and normal used by a face corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Corner {
+    pub vertex: usize,
+    pub texture: Option<usize>,
+    pub normal: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Face {
-    pub vertices: Vec<Rc<Vertex>>,
-    pub vertices_texture: Vec<Rc<VertexTexture>>,
-    pub vertices_normal: Vec<Rc<VertexNormal>>,
+    pub corners: Vec<Corner>,
+    /// Smoothing group the face belongs to, see [`SMOOTHING_GROUP_OFF`].
+    pub smoothing_group: u32,
+    /// Index in [`Object::materials`] of the material set by `usemtl`.
+    pub material: Option<usize>,
 }
 
 impl Face {
     pub fn new() -> Self {
         Face {
-            vertices: vec![],
-            vertices_texture: vec![],
-            vertices_normal: vec![],
+            corners: vec![],
+            smoothing_group: SMOOTHING_GROUP_OFF,
+            material: None,
         }
     }
 
-    pub fn push_arg(&mut self, v: Option<usize>, vt: Option<usize>, vn: Option<usize>, object: &Object) -> Result<(), io::Error> {
-        Self::push(v, &mut self.vertices, &object.vertices)?;
-        Self::push(vt, &mut self.vertices_texture, &object.vertices_texture)?;
-        Self::push(vn, &mut self.vertices_normal, &object.vertices_normal)?;
-        Ok(())
+    /// Whether the face shares its vertex normals with the neighbouring faces
+    /// of its smoothing group.
+    pub fn is_smooth(&self) -> bool {
+        self.smoothing_group != SMOOTHING_GROUP_OFF
+    }
+
+    /// Creates a face sharing the attributes of this one but other corners.
+    pub fn with_corners(&self, corners: Vec<Corner>) -> Self {
+        Face {
+            corners,
+            smoothing_group: self.smoothing_group,
+            material: self.material,
+        }
     }
 
-    fn push<T>(opt: Option<usize>, face_vec: &mut Vec<Rc<T>>, obj_vec: &Vec<Rc<T>>) -> Result<(), io::Error> {
-        if let Some(index) = opt {
-            match obj_vec.get(index - 1) {
-                Some(vertex) => face_vec.push(Rc::clone(vertex)),
-                None => return Err(error::index_out_of_bound(index, obj_vec.len())),
-            }
+    /// Newell's normal of the polygon, whose length is twice its area.
+    pub fn area_normal(&self, vertices: &[Vertex]) -> [f32; 3] {
+        let mut normal = [0.0; 3];
+        for (i, corner) in self.corners.iter().enumerate() {
+            let current = vertices[corner.vertex].position();
+            let next = vertices[self.corners[(i + 1) % self.corners.len()].vertex].position();
+            normal[0] += (current[1] - next[1]) * (current[2] + next[2]);
+            normal[1] += (current[2] - next[2]) * (current[0] + next[0]);
+            normal[2] += (current[0] - next[0]) * (current[1] + next[1]);
         }
+        normal
+    }
+
+    /// Appends a face corner. Indices are 1-based, negative ones being relative
+    /// to the end of the lists parsed so far (`-1` is the last element).
+    pub fn push_arg(&mut self, v: isize, vt: Option<isize>, vn: Option<isize>, object: &Object) -> Result<(), StatementError> {
+        let corner = Corner {
+            vertex: resolve_index(v, object.vertices.len())?,
+            texture: resolve_optional_index(vt, object.vertices_texture.len())?,
+            normal: resolve_optional_index(vn, object.vertices_normal.len())?,
+        };
+        self.corners.push(corner);
         Ok(())
     }
 }
+
+impl Default for Face {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::object::{Face, Vertex};
+
+const EPSILON: f32 = 1e-12;
+
+type Point = [f32; 2];
+
+impl Face {
+    /// Splits the face into triangles by ear clipping on the plane the polygon
+    /// is projected onto, which handles concave faces that a fan would break.
+    /// Each triangle keeps the texture and normal references of its corners.
+    pub fn triangulate(&self, vertices: &[Vertex]) -> Vec<Face> {
+        let len = self.corners.len();
+        if len <= 3 {
+            return vec![self.select(&[0, 1, 2][..len])];
+        }
+        let points = self.project(vertices);
+        let mut remaining: Vec<usize> = (0..len).collect();
+        let mut triangles = Vec::with_capacity(len - 2);
+        while remaining.len() > 3 {
+            match find_ear(&points, &remaining) {
+                Some(ear) => {
+                    let count = remaining.len();
+                    let prev = remaining[(ear + count - 1) % count];
+                    let next = remaining[(ear + 1) % count];
+                    triangles.push(self.select(&[prev, remaining[ear], next]));
+                    remaining.remove(ear);
+                }
+                // Degenerate or self-intersecting polygon: fan what is left.
+                None => break,
+            }
+        }
+        for i in 1..remaining.len() - 1 {
+            triangles.push(self.select(&[remaining[0], remaining[i], remaining[i + 1]]));
+        }
+        triangles
+    }
+
+    fn select(&self, corners: &[usize]) -> Face {
+        self.with_corners(corners.iter().map(|&i| self.corners[i]).collect())
+    }
+
+    /// Projects the polygon on the axis-aligned plane closest to its own,
+    /// oriented so that the polygon winds counter-clockwise.
+    fn project(&self, vertices: &[Vertex]) -> Vec<Point> {
+        let positions: Vec<[f32; 3]> = self.corners.iter()
+            .map(|corner| vertices[corner.vertex].position())
+            .collect();
+        let normal = self.area_normal(vertices);
+        let axis = (0..3)
+            .max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs()))
+            .unwrap_or(2);
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let flip = if normal[axis] < 0.0 { -1.0 } else { 1.0 };
+        positions.iter()
+            .map(|position| [position[u], position[v] * flip])
+            .collect()
+    }
+}
+
+fn find_ear(points: &[Point], remaining: &[usize]) -> Option<usize> {
+    let count = remaining.len();
+    (0..count).find(|&i| {
+        let a = points[remaining[(i + count - 1) % count]];
+        let b = points[remaining[i]];
+        let c = points[remaining[(i + 1) % count]];
+        if cross(a, b, c) <= EPSILON {
+            return false;
+        }
+        !remaining.iter()
+            .map(|&j| points[j])
+            .filter(|&p| p != a && p != b && p != c)
+            .any(|p| contains(a, b, c, p))
+    })
+}
+
+/// Twice the signed area of the triangle `abc`, positive when counter-clockwise.
+fn cross(a: Point, b: Point, c: Point) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn contains(a: Point, b: Point, c: Point, p: Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
@@ -0,0 +1,190 @@
+mod basis;
+mod tessellation;
+
+use crate::error::{self, StatementError};
+use crate::object::{resolve_index, resolve_optional_index, Corner, Object};
+
+/// Index of the u direction of the parameter space.
+pub const U: usize = 0;
+/// Index of the v direction of the parameter space, which only surfaces have.
+pub const V: usize = 1;
+
+/// Polynomial basis of the free-form elements, set by `cstype`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    /// Basis given by `bmat`.
+    BasisMatrix,
+    Bezier,
+    BSpline,
+    /// Cubic Catmull-Rom spline.
+    Cardinal,
+    /// Power basis, the control points being the coefficients of the
+    /// polynomials.
+    Taylor,
+}
+
+/// Attributes in effect when a free-form element is declared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeFormAttributes {
+    pub curve_type: CurveType,
+    /// Whether the weights of the control points apply.
+    pub rational: bool,
+    /// Degree in each direction, the v one being 0 for curves.
+    pub degree: [usize; 2],
+    /// Matrices of a [`CurveType::BasisMatrix`] basis in each direction, as
+    /// written by `bmat`: row `i` holds the coefficients of the increasing
+    /// powers of the parameter weighting the `i`-th control point of a
+    /// segment.
+    pub basis_matrices: [Vec<f32>; 2],
+    /// Control points between the start of two consecutive segments of a
+    /// [`CurveType::BasisMatrix`] basis.
+    pub step: [usize; 2],
+}
+
+/// Curve in model space declared by `curv`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    /// Parameters the curve starts and ends at.
+    pub range: [f32; 2],
+    pub vertices: Vec<usize>,
+    /// Global parameters set by `parm u`, the knot vector of a B-spline.
+    pub parameters: Vec<f32>,
+    pub attributes: FreeFormAttributes,
+    /// Index in [`Object::materials`] of the material set by `usemtl`.
+    pub material: Option<usize>,
+}
+
+/// Curve in the parameter space of a surface declared by `curv2`, its control
+/// points indexing [`Object::vertices_parameter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve2 {
+    pub vertices_parameter: Vec<usize>,
+    pub parameters: Vec<f32>,
+    pub attributes: FreeFormAttributes,
+}
+
+/// Part of a trimming loop, the range of a curve of [`Object::curves2`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrimCurve {
+    pub range: [f32; 2],
+    pub curve: usize,
+}
+
+/// Surface declared by `surf`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Surface {
+    /// Parameters the surface starts and ends at in each direction.
+    pub range: [[f32; 2]; 2],
+    /// Control points, the u direction varying fastest.
+    pub corners: Vec<Corner>,
+    /// Global parameters set by `parm u` and `parm v`.
+    pub parameters: [Vec<f32>; 2],
+    /// Loops set by `trim`, outside of which the surface is cut away.
+    pub trims: Vec<Vec<TrimCurve>>,
+    /// Loops set by `hole`, inside of which the surface is cut away.
+    pub holes: Vec<Vec<TrimCurve>>,
+    pub attributes: FreeFormAttributes,
+    /// Smoothing group of the faces it is tessellated into.
+    pub smoothing_group: u32,
+    /// Index in [`Object::materials`] of the material set by `usemtl`.
+    pub material: Option<usize>,
+}
+
+impl Curve {
+    pub fn new(range: [f32; 2], attributes: FreeFormAttributes) -> Self {
+        Curve {
+            range,
+            vertices: vec![],
+            parameters: vec![],
+            attributes,
+            material: None,
+        }
+    }
+
+    /// Appends a control point, indices following the same rules as
+    /// [`Face::push_arg`](crate::object::Face::push_arg).
+    pub fn push_arg(&mut self, v: isize, object: &Object) -> Result<(), StatementError> {
+        self.vertices.push(resolve_index(v, object.vertices.len())?);
+        Ok(())
+    }
+
+    /// Checks that the control points match the degree and the parameters.
+    pub fn check(&self) -> Result<(), StatementError> {
+        basis::Basis::new(&self.attributes, U, &self.parameters)?
+            .check(self.vertices.len())
+    }
+}
+
+impl Curve2 {
+    pub fn new(attributes: FreeFormAttributes) -> Self {
+        Curve2 {
+            vertices_parameter: vec![],
+            parameters: vec![],
+            attributes,
+        }
+    }
+
+    pub fn push_arg(&mut self, vp: isize, object: &Object) -> Result<(), StatementError> {
+        let index = resolve_index(vp, object.vertices_parameter.len())?;
+        if object.vertices_parameter[index].v.is_none() {
+            return Err(error::missing_parameter_v());
+        }
+        self.vertices_parameter.push(index);
+        Ok(())
+    }
+
+    pub fn check(&self) -> Result<(), StatementError> {
+        basis::Basis::new(&self.attributes, U, &self.parameters)?
+            .check(self.vertices_parameter.len())
+    }
+}
+
+impl TrimCurve {
+    pub fn new(range: [f32; 2], curve: isize, object: &Object) -> Result<Self, StatementError> {
+        Ok(TrimCurve {
+            range,
+            curve: resolve_index(curve, object.curves2.len())?,
+        })
+    }
+}
+
+impl Surface {
+    pub fn new(range: [[f32; 2]; 2], attributes: FreeFormAttributes) -> Self {
+        Surface {
+            range,
+            corners: vec![],
+            parameters: [vec![], vec![]],
+            trims: vec![],
+            holes: vec![],
+            attributes,
+            smoothing_group: 0,
+            material: None,
+        }
+    }
+
+    /// Appends a control point, which like a face corner may reference a
+    /// texture coordinate and a normal.
+    pub fn push_arg(&mut self, v: isize, vt: Option<isize>, vn: Option<isize>, object: &Object) -> Result<(), StatementError> {
+        let corner = Corner {
+            vertex: resolve_index(v, object.vertices.len())?,
+            texture: resolve_optional_index(vt, object.vertices_texture.len())?,
+            normal: resolve_optional_index(vn, object.vertices_normal.len())?,
+        };
+        if self.corners.first().is_some_and(|first| {
+            first.texture.is_some() != corner.texture.is_some()
+                || first.normal.is_some() != corner.normal.is_some()
+        }) {
+            return Err(error::invalid_statement());
+        }
+        self.corners.push(corner);
+        Ok(())
+    }
+
+    /// Checks that the control points match the degrees and the parameters
+    /// of both directions.
+    pub fn check(&self) -> Result<(), StatementError> {
+        let u = basis::Basis::new(&self.attributes, U, &self.parameters[U])?;
+        let v = basis::Basis::new(&self.attributes, V, &self.parameters[V])?;
+        u.check_grid(&v, self.corners.len())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::error::{self, StatementError};
+use crate::object::free_form::{CurveType, FreeFormAttributes};
+
+/// Coefficients of the cubic Catmull-Rom basis, laid out like `bmat`.
+const CARDINAL_MATRIX: [f32; 16] = [
+    0.0, -0.5, 1.0, -0.5,
+    1.0, 0.0, -2.5, 1.5,
+    0.0, 0.5, 2.0, -1.5,
+    0.0, 0.0, -0.5, 0.5,
+];
+const CARDINAL_DEGREE: usize = 3;
+/// Highest degree accepted, far above what modelling tools export, which
+/// bounds the size of the basis matrices built before the control points are
+/// counted and keeps their binomial coefficients within `f32`.
+const MAX_DEGREE: usize = 32;
+
+/// Basis functions of one direction of a free-form element.
+pub struct Basis<'a> {
+    kind: Kind,
+    degree: usize,
+    parameters: &'a [f32],
+}
+
+enum Kind {
+    /// Piecewise polynomials over knot spans, evaluated with the Cox-de Boor
+    /// recurrence.
+    Spline,
+    /// Segments spanning two consecutive parameters, each weighting `degree + 1`
+    /// control points through a basis matrix, the first control point moving
+    /// `step` places from one segment to the next.
+    Matrix {
+        matrix: Vec<f32>,
+        step: usize,
+    },
+}
+
+impl<'a> Basis<'a> {
+    pub fn new(attributes: &FreeFormAttributes, direction: usize, parameters: &'a [f32]) -> Result<Self, StatementError> {
+        let degree = attributes.degree[direction];
+        if degree == 0 || degree > MAX_DEGREE {
+            return Err(error::invalid_degree());
+        }
+        let (kind, min_parameters) = match attributes.curve_type {
+            CurveType::BSpline => (Kind::Spline, 2 * degree + 2),
+            CurveType::Bezier => (Kind::Matrix { matrix: bernstein_matrix(degree), step: degree }, 2),
+            CurveType::Taylor => (Kind::Matrix { matrix: identity_matrix(degree), step: degree + 1 }, 2),
+            CurveType::Cardinal if degree == CARDINAL_DEGREE => {
+                (Kind::Matrix { matrix: CARDINAL_MATRIX.to_vec(), step: 1 }, 2)
+            }
+            CurveType::Cardinal => return Err(error::invalid_degree()),
+            CurveType::BasisMatrix => {
+                let matrix = &attributes.basis_matrices[direction];
+                let step = attributes.step[direction];
+                if matrix.len() != (degree + 1) * (degree + 1) {
+                    return Err(error::invalid_basis_matrix((degree + 1) * (degree + 1), matrix.len()));
+                }
+                if step == 0 {
+                    return Err(error::invalid_step());
+                }
+                (Kind::Matrix { matrix: matrix.clone(), step }, 2)
+            }
+        };
+        if parameters.len() < min_parameters {
+            return Err(error::invalid_parameter_count(min_parameters, parameters.len()));
+        }
+        if parameters.iter().any(|parameter| !parameter.is_finite()) {
+            return Err(error::invalid_parameters());
+        }
+        let basis = Basis { kind, degree, parameters };
+        let [start, end] = basis.domain();
+        if parameters.windows(2).any(|pair| pair[0] > pair[1]) || start >= end {
+            return Err(error::invalid_parameters());
+        }
+        Ok(basis)
+    }
+
+    /// Number of control points the parameters call for.
+    pub fn control_count(&self) -> usize {
+        match &self.kind {
+            Kind::Spline => self.parameters.len() - self.degree - 1,
+            Kind::Matrix { step, .. } => (self.parameters.len() - 2) * step + self.degree + 1,
+        }
+    }
+
+    pub fn check(&self, control_count: usize) -> Result<(), StatementError> {
+        if control_count != self.control_count() {
+            return Err(error::invalid_control_point_count(self.control_count(), control_count));
+        }
+        Ok(())
+    }
+
+    /// Checks a grid of control points whose rows follow this basis and whose
+    /// columns follow `other`.
+    pub fn check_grid(&self, other: &Basis, control_count: usize) -> Result<(), StatementError> {
+        let expected = self.control_count() * other.control_count();
+        if control_count != expected {
+            return Err(error::invalid_control_point_count(expected, control_count));
+        }
+        Ok(())
+    }
+
+    /// Parameters over which the basis functions sum to one.
+    pub fn domain(&self) -> [f32; 2] {
+        let last = self.parameters.len() - 1;
+        match self.kind {
+            Kind::Spline => [self.parameters[self.degree], self.parameters[last - self.degree]],
+            Kind::Matrix { .. } => [self.parameters[0], self.parameters[last]],
+        }
+    }
+
+    /// Parameters within the domain at which the polynomials change.
+    pub fn breakpoints(&self) -> impl Iterator<Item = f32> + '_ {
+        let [start, end] = self.domain();
+        self.parameters.iter()
+            .copied()
+            .filter(move |&parameter| start <= parameter && parameter <= end)
+    }
+
+    /// Writes the non-zero basis functions at `t`, clamped to the domain, into
+    /// `weights` as pairs of control point index and value.
+    pub fn evaluate(&self, t: f32, weights: &mut Vec<(usize, f32)>) {
+        weights.clear();
+        let [start, end] = self.domain();
+        let t = t.clamp(start, end);
+        match &self.kind {
+            Kind::Spline => self.spline_evaluate(t, weights),
+            Kind::Matrix { matrix, step } => {
+                let segment = self.span(t);
+                let (low, high) = (self.parameters[segment], self.parameters[segment + 1]);
+                let local = if high > low { (t - low) / (high - low) } else { 0.0 };
+                let order = self.degree + 1;
+                for (i, row) in matrix.chunks(order).enumerate() {
+                    let value = row.iter().rev().fold(0.0, |sum, &coefficient| sum * local + coefficient);
+                    weights.push((segment * step + i, value));
+                }
+            }
+        }
+    }
+
+    /// Cox-de Boor recurrence, as laid out in "The NURBS Book" (A2.2).
+    fn spline_evaluate(&self, t: f32, weights: &mut Vec<(usize, f32)>) {
+        let knots = self.parameters;
+        let span = self.span(t);
+        let mut values = vec![0.0; self.degree + 1];
+        let mut left = vec![0.0; self.degree + 1];
+        let mut right = vec![0.0; self.degree + 1];
+        values[0] = 1.0;
+        for j in 1..=self.degree {
+            left[j] = t - knots[span + 1 - j];
+            right[j] = knots[span + j] - t;
+            let mut saved = 0.0;
+            for r in 0..j {
+                let temp = values[r] / (right[r + 1] + left[j - r]);
+                values[r] = saved + right[r + 1] * temp;
+                saved = left[j - r] * temp;
+            }
+            values[j] = saved;
+        }
+        weights.extend(values.into_iter()
+            .enumerate()
+            .map(|(r, value)| (span - self.degree + r, value)));
+    }
+
+    /// Index of the last non-empty knot span or segment starting at or before `t`.
+    fn span(&self, t: f32) -> usize {
+        let (first, last) = match self.kind {
+            Kind::Spline => (self.degree, self.parameters.len() - self.degree - 2),
+            Kind::Matrix { .. } => (0, self.parameters.len() - 2),
+        };
+        (first..=last)
+            .rev()
+            .find(|&i| self.parameters[i] <= t && self.parameters[i] < self.parameters[i + 1])
+            .unwrap_or(first)
+    }
+}
+
+/// Bernstein polynomials of `degree`, whose `i`-th has the coefficients
+/// `C(n, i) C(n - i, j - i) (-1)^(j - i)` for the powers `j >= i`.
+fn bernstein_matrix(degree: usize) -> Vec<f32> {
+    let order = degree + 1;
+    let mut matrix = vec![0.0; order * order];
+    for i in 0..order {
+        for j in i..order {
+            let sign = if (j - i) % 2 == 0 { 1.0 } else { -1.0 };
+            matrix[i * order + j] = sign * binomial(degree, i) * binomial(degree - i, j - i);
+        }
+    }
+    matrix
+}
+
+fn identity_matrix(degree: usize) -> Vec<f32> {
+    let order = degree + 1;
+    (0..order * order)
+        .map(|i| if i % (order + 1) == 0 { 1.0 } else { 0.0 })
+        .collect()
+}
+
+fn binomial(n: usize, k: usize) -> f32 {
+    (0..k).fold(1.0, |value, i| value * (n - i) as f32 / (i + 1) as f32)
+}
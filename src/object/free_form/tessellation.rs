@@ -0,0 +1,302 @@
+use crate::error::StatementError;
+use crate::object::free_form::basis::Basis;
+use crate::object::free_form::{Curve, Surface, TrimCurve, U, V};
+use crate::object::vector::{self, Vector3};
+use crate::object::{Corner, Face, Line, Object, Vertex, VertexNormal, VertexTexture};
+
+type Point = [f32; 2];
+
+/// Step of the differences approximating the tangents, relative to the
+/// domain of the surface.
+const DIFFERENCE_STEP: f32 = 1e-3;
+/// Sine of the angle under which tangents are taken as parallel.
+const PARALLEL_SINE: f32 = 1e-4;
+
+impl Curve {
+    /// Samples the curve into a polyline, each knot span or segment being
+    /// split into `resolution` segments. The vertices are appended to `mesh`,
+    /// which the corners of the polyline index.
+    pub fn tessellate(&self, object: &Object, resolution: usize, mesh: &mut Object) -> Result<Line, StatementError> {
+        let basis = Basis::new(&self.attributes, U, &self.parameters)?;
+        basis.check(self.vertices.len())?;
+        let mut line = Line::new();
+        line.material = self.material;
+        let mut weights = vec![];
+        for t in samples(&basis, self.range, resolution) {
+            basis.evaluate(t, &mut weights);
+            let [x, y, z] = blend(&weights, self.attributes.rational, |i| {
+                let vertex = object.vertices[self.vertices[i]];
+                (vertex.position(), vertex.w)
+            });
+            mesh.add_vertex(Vertex::new(x, y, z, None));
+            line.corners.push(Corner {
+                vertex: mesh.vertices.len() - 1,
+                texture: None,
+                normal: None,
+            });
+        }
+        Ok(line)
+    }
+}
+
+impl Surface {
+    /// Samples the surface on a grid, each knot span or segment being split
+    /// into `resolution` parts in both directions, and returns the triangles
+    /// left by the trimming loops. The vertices, texture coordinates and
+    /// normals are appended to `mesh`, which the corners of the faces index.
+    ///
+    /// Texture coordinates are interpolated from the ones of the control
+    /// points, or else follow the parameters across the range. Normals are
+    /// derived from the tangents of the surface, or averaged over the
+    /// triangles of the grid where these are parallel, as at a pole.
+    ///
+    /// Trimming loops are not clipped against: a triangle of the grid is
+    /// kept or dropped whole depending on whether its centroid lies inside
+    /// them, so that cut edges follow the grid as a staircase, closer to the
+    /// loops as the resolution increases.
+    pub fn tessellate(&self, object: &Object, resolution: usize, mesh: &mut Object) -> Result<Vec<Face>, StatementError> {
+        let mut patch = Patch::new(self, object)?;
+        let trims = loops(&self.trims, object, resolution)?;
+        let holes = loops(&self.holes, object, resolution)?;
+
+        let samples_u = samples(&patch.bases[U], self.range[U], resolution);
+        let samples_v = samples(&patch.bases[V], self.range[V], resolution);
+        let first_vertex = mesh.vertices.len();
+        let first_texture = mesh.vertices_texture.len();
+        let first_normal = mesh.vertices_normal.len();
+        let mut positions = Vec::with_capacity(samples_u.len() * samples_v.len());
+        let mut tangent_normals = Vec::with_capacity(positions.capacity());
+        for &v in &samples_v {
+            for &u in &samples_u {
+                let position = patch.position(u, v);
+                let texture = match self.corners.iter().all(|corner| corner.texture.is_some()) {
+                    true => patch.texture(u, v),
+                    false => [fraction(self.range[U], u), fraction(self.range[V], v)],
+                };
+                positions.push(position);
+                tangent_normals.push(patch.normal(u, v));
+                mesh.add_vertex(Vertex::new(position[0], position[1], position[2], None));
+                mesh.add_vertex_texture(VertexTexture::new(texture[0], Some(texture[1]), None));
+            }
+        }
+
+        let width = samples_u.len();
+        let mut triangles = vec![];
+        for j in 1..samples_v.len() {
+            for i in 1..width {
+                let quad = [
+                    (j - 1) * width + i - 1,
+                    (j - 1) * width + i,
+                    j * width + i,
+                    j * width + i - 1,
+                ];
+                triangles.push([quad[0], quad[1], quad[2]]);
+                triangles.push([quad[0], quad[2], quad[3]]);
+            }
+        }
+        let mut normals = vec![[0.0; 3]; positions.len()];
+        for triangle in &triangles {
+            let [a, b, c] = triangle.map(|index| positions[index]);
+            let normal = vector::cross(vector::sub(b, a), vector::sub(c, a));
+            for &index in triangle {
+                normals[index] = vector::add(normals[index], normal);
+            }
+        }
+        // The grid runs against the tangents along a reversed range.
+        let orientation = if (self.range[U][0] > self.range[U][1]) != (self.range[V][0] > self.range[V][1]) {
+            -1.0
+        } else {
+            1.0
+        };
+        for (normal, tangent_normal) in normals.into_iter().zip(tangent_normals) {
+            let normal = match tangent_normal {
+                Some(tangent_normal) => vector::scale(tangent_normal, orientation),
+                None => normal,
+            };
+            let [i, j, k] = vector::normalize(normal);
+            mesh.add_vertex_normal(VertexNormal::new(i, j, k));
+        }
+
+        let parameter = |index: usize| [samples_u[index % width], samples_v[index / width]];
+        let faces = triangles.into_iter()
+            .filter(|triangle| {
+                let [a, b, c] = triangle.map(parameter);
+                let centroid = [(a[0] + b[0] + c[0]) / 3.0, (a[1] + b[1] + c[1]) / 3.0];
+                (trims.is_empty() || trims.iter().any(|trim| contains(trim, centroid)))
+                    && !holes.iter().any(|hole| contains(hole, centroid))
+            })
+            .map(|triangle| Face {
+                corners: triangle.iter()
+                    .map(|&index| Corner {
+                        vertex: first_vertex + index,
+                        texture: Some(first_texture + index),
+                        normal: Some(first_normal + index),
+                    })
+                    .collect(),
+                smoothing_group: self.smoothing_group,
+                material: self.material,
+            })
+            .collect();
+        Ok(faces)
+    }
+}
+
+/// Surface evaluated at any parameters of its domain.
+struct Patch<'a> {
+    surface: &'a Surface,
+    object: &'a Object,
+    bases: [Basis<'a>; 2],
+    weights: [Vec<(usize, f32)>; 2],
+    combined: Vec<(usize, f32)>,
+}
+
+impl<'a> Patch<'a> {
+    fn new(surface: &'a Surface, object: &'a Object) -> Result<Self, StatementError> {
+        let basis_u = Basis::new(&surface.attributes, U, &surface.parameters[U])?;
+        let basis_v = Basis::new(&surface.attributes, V, &surface.parameters[V])?;
+        basis_u.check_grid(&basis_v, surface.corners.len())?;
+        Ok(Patch {
+            surface,
+            object,
+            bases: [basis_u, basis_v],
+            weights: [vec![], vec![]],
+            combined: vec![],
+        })
+    }
+
+    /// Computes the weights of the control points at `(u, v)`.
+    fn evaluate(&mut self, u: f32, v: f32) {
+        let row = self.bases[U].control_count();
+        let [weights_u, weights_v] = &mut self.weights;
+        self.bases[U].evaluate(u, weights_u);
+        self.bases[V].evaluate(v, weights_v);
+        self.combined.clear();
+        self.combined.extend(weights_v.iter().flat_map(|&(j, b)| {
+            weights_u.iter().map(move |&(i, a)| (j * row + i, a * b))
+        }));
+    }
+
+    fn position(&mut self, u: f32, v: f32) -> Vector3 {
+        self.evaluate(u, v);
+        let (surface, object) = (self.surface, self.object);
+        blend(&self.combined, surface.attributes.rational, |i| {
+            let vertex = object.vertices[surface.corners[i].vertex];
+            (vertex.position(), vertex.w)
+        })
+    }
+
+    fn texture(&mut self, u: f32, v: f32) -> [f32; 2] {
+        self.evaluate(u, v);
+        let (surface, object) = (self.surface, self.object);
+        let [s, t, _] = blend(&self.combined, surface.attributes.rational, |i| {
+            let corner = surface.corners[i];
+            let texture = corner.texture.map_or(VertexTexture::new(0.0, None, None), |index| {
+                object.vertices_texture[index]
+            });
+            ([texture.u, texture.v.unwrap_or(0.0), 0.0], object.vertices[corner.vertex].w)
+        });
+        [s, t]
+    }
+
+    /// Cross product of the tangents, approximated by differences over a
+    /// small step of the parameters, or `None` where they are parallel.
+    fn normal(&mut self, u: f32, v: f32) -> Option<Vector3> {
+        let [u0, u1] = self.neighbours(U, u);
+        let [v0, v1] = self.neighbours(V, v);
+        let tangent_u = vector::sub(self.position(u1, v), self.position(u0, v));
+        let tangent_v = vector::sub(self.position(u, v1), self.position(u, v0));
+        let normal = vector::cross(tangent_u, tangent_v);
+        let sine = vector::length(normal) / (vector::length(tangent_u) * vector::length(tangent_v));
+        (sine > PARALLEL_SINE).then_some(normal)
+    }
+
+    /// Parameters around `t` within the domain.
+    fn neighbours(&self, direction: usize, t: f32) -> [f32; 2] {
+        let [start, end] = self.bases[direction].domain();
+        let step = (end - start) * DIFFERENCE_STEP;
+        [(t - step).max(start), (t + step).min(end)]
+    }
+}
+
+/// Samples trimming loops into polygons of the parameter space.
+fn loops(loops: &[Vec<TrimCurve>], object: &Object, resolution: usize) -> Result<Vec<Vec<Point>>, StatementError> {
+    let mut weights = vec![];
+    loops.iter()
+        .map(|trim_loop| {
+            let mut polygon = vec![];
+            for trim in trim_loop {
+                let curve = &object.curves2[trim.curve];
+                let basis = Basis::new(&curve.attributes, U, &curve.parameters)?;
+                basis.check(curve.vertices_parameter.len())?;
+                for t in samples(&basis, trim.range, resolution) {
+                    basis.evaluate(t, &mut weights);
+                    let [u, v, _] = blend(&weights, curve.attributes.rational, |i| {
+                        let vertex = object.vertices_parameter[curve.vertices_parameter[i]];
+                        ([vertex.u, vertex.v.unwrap_or(0.0), 0.0], vertex.w)
+                    });
+                    polygon.push([u, v]);
+                }
+            }
+            Ok(polygon)
+        })
+        .collect()
+}
+
+/// Parameters sampling `range`, clamped to the domain of `basis`, in
+/// `resolution` steps between consecutive breakpoints.
+fn samples(basis: &Basis, range: [f32; 2], resolution: usize) -> Vec<f32> {
+    let [start, end] = basis.domain();
+    let low = range[0].min(range[1]).clamp(start, end);
+    let high = range[0].max(range[1]).clamp(start, end);
+    let mut breakpoints = vec![low];
+    breakpoints.extend(basis.breakpoints().filter(|&t| low < t && t < high));
+    breakpoints.push(high);
+    breakpoints.dedup();
+    let mut samples = vec![breakpoints[0]];
+    for pair in breakpoints.windows(2) {
+        samples.extend((1..=resolution).map(|i| pair[0] + (pair[1] - pair[0]) * i as f32 / resolution as f32));
+    }
+    if range[0] > range[1] {
+        samples.reverse();
+    }
+    samples
+}
+
+/// Sum of the control points weighted by the basis functions, `control`
+/// giving the position and the weight of each. The weights only apply to
+/// rational elements, whose sum is then normalized.
+fn blend(weights: &[(usize, f32)], rational: bool, control: impl Fn(usize) -> (Vector3, f32)) -> Vector3 {
+    let mut sum = [0.0; 3];
+    let mut total = 0.0;
+    for &(index, value) in weights {
+        let (position, weight) = control(index);
+        let weight = if rational { value * weight } else { value };
+        sum = vector::add(sum, vector::scale(position, weight));
+        total += weight;
+    }
+    if !rational || total == 0.0 {
+        return sum;
+    }
+    vector::scale(sum, 1.0 / total)
+}
+
+/// Position of `t` across `range`, from 0 at its start to 1 at its end.
+fn fraction(range: [f32; 2], t: f32) -> f32 {
+    if range[0] == range[1] {
+        return 0.0;
+    }
+    (t - range[0]) / (range[1] - range[0])
+}
+
+/// Even-odd rule.
+fn contains(polygon: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + polygon.len() - 1) % polygon.len()];
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < (b[0] - a[0]) * (point[1] - a[1]) / (b[1] - a[1]) + a[0] {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -0,0 +1,120 @@
+use std::ops::Range;
+use ahash::{HashMap, HashMapExt};
+use crate::object::{Corner, Object};
+
+/// A vertex with every attribute of a face corner packed together.
+/// Missing texture coordinates and normals are zeroed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterleavedVertex {
+    pub position: [f32; 3],
+    pub color: Option<[f32; 3]>,
+    pub texture: [f32; 2],
+    pub normal: [f32; 3],
+    pub material: Option<usize>,
+}
+
+/// Triangle, line and point lists where each distinct `(v, vt, vn)` corner
+/// is stored once per material using it. Vertices are numbered in the order
+/// they are first used, by the triangles, then the lines, then the points.
+#[derive(Debug, Clone)]
+pub struct IndexedMesh {
+    pub vertices: Vec<InterleavedVertex>,
+    pub indices: Vec<u32>,
+    /// Part of `indices` holding the triangles of each face of the object.
+    pub face_ranges: Vec<Range<usize>>,
+    /// Pairs of vertices joined by each segment of the `l` elements.
+    pub line_indices: Vec<u32>,
+    pub point_indices: Vec<u32>,
+}
+
+impl IndexedMesh {
+    pub fn unique_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Vertices the triangles use, which come before the ones only lines and
+    /// points use.
+    pub fn triangle_vertices(&self) -> &[InterleavedVertex] {
+        let end = self.indices.iter().max().map_or(0, |&max| max as usize + 1);
+        &self.vertices[..end]
+    }
+
+    pub fn triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn segments(&self) -> usize {
+        self.line_indices.len() / 2
+    }
+
+    pub fn points(&self) -> usize {
+        self.point_indices.len()
+    }
+}
+
+impl Object {
+    /// Builds indexed triangle, line and point lists out of the elements,
+    /// triangulating polygons and sharing a single vertex between identical
+    /// corners.
+    pub fn indexed_mesh(&self) -> IndexedMesh {
+        let mut mesh = IndexedMesh {
+            vertices: vec![],
+            indices: vec![],
+            face_ranges: Vec::with_capacity(self.faces.len()),
+            line_indices: vec![],
+            point_indices: vec![],
+        };
+        let mut known: HashMap<(Corner, Option<usize>), u32> = HashMap::new();
+        let mut index_of = |corner: Corner, material: Option<usize>, vertices: &mut Vec<InterleavedVertex>| {
+            *known.entry((corner, material)).or_insert_with(|| {
+                vertices.push(self.interleave(corner, material));
+                (vertices.len() - 1) as u32
+            })
+        };
+        for face in &self.faces {
+            let start = mesh.indices.len();
+            for triangle in face.triangulate(&self.vertices) {
+                for corner in triangle.corners {
+                    let index = index_of(corner, face.material, &mut mesh.vertices);
+                    mesh.indices.push(index);
+                }
+            }
+            mesh.face_ranges.push(start..mesh.indices.len());
+        }
+        for line in &self.lines {
+            for corner in line.segments().flatten() {
+                let index = index_of(corner, line.material, &mut mesh.vertices);
+                mesh.line_indices.push(index);
+            }
+        }
+        for point in &self.points {
+            for &vertex in &point.vertices {
+                let corner = Corner {
+                    vertex,
+                    texture: None,
+                    normal: None,
+                };
+                let index = index_of(corner, point.material, &mut mesh.vertices);
+                mesh.point_indices.push(index);
+            }
+        }
+        mesh
+    }
+
+    fn interleave(&self, corner: Corner, material: Option<usize>) -> InterleavedVertex {
+        let texture = corner.texture
+            .map(|index| &self.vertices_texture[index])
+            .map_or([0.0; 2], |vt| [vt.u, vt.v.unwrap_or(0.0)]);
+        let normal = corner.normal
+            .map(|index| &self.vertices_normal[index])
+            .map_or([0.0; 3], |vn| [vn.i, vn.j, vn.k]);
+        let vertex = &self.vertices[corner.vertex];
+        InterleavedVertex {
+            position: vertex.position(),
+            color: vertex.color,
+            texture,
+            normal,
+            material,
+        }
+    }
+}
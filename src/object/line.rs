@@ -0,0 +1,42 @@
+use crate::error::StatementError;
+use crate::object::{resolve_index, resolve_optional_index, Corner, Object};
+
+/// Polyline declared by `l`. Its corners never reference a normal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub corners: Vec<Corner>,
+    /// Index in [`Object::materials`] of the material set by `usemtl`.
+    pub material: Option<usize>,
+}
+
+impl Line {
+    pub fn new() -> Self {
+        Line {
+            corners: vec![],
+            material: None,
+        }
+    }
+
+    /// Pairs of corners joined by each segment of the polyline.
+    pub fn segments(&self) -> impl Iterator<Item = [Corner; 2]> + '_ {
+        self.corners.windows(2).map(|pair| [pair[0], pair[1]])
+    }
+
+    /// Appends a point of the polyline, indices following the same rules as
+    /// [`Face::push_arg`](crate::object::Face::push_arg).
+    pub fn push_arg(&mut self, v: isize, vt: Option<isize>, object: &Object) -> Result<(), StatementError> {
+        let corner = Corner {
+            vertex: resolve_index(v, object.vertices.len())?,
+            texture: resolve_optional_index(vt, object.vertices_texture.len())?,
+            normal: None,
+        };
+        self.corners.push(corner);
+        Ok(())
+    }
+}
+
+impl Default for Line {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,154 @@
+/// Channel of the image used by scalar maps (`-imfchan`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageChannel {
+    Red,
+    Green,
+    Blue,
+    Matte,
+    Luminance,
+    Depth,
+}
+
+/// Image referenced by a `map_*` statement of a material library, along with
+/// the options written before the file name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMap {
+    pub file: String,
+    /// `-blendu`
+    pub blend_u: bool,
+    /// `-blendv`
+    pub blend_v: bool,
+    /// `-bm`
+    pub bump_multiplier: f32,
+    /// `-boost`
+    pub boost: Option<f32>,
+    /// `-cc`
+    pub color_correction: bool,
+    /// `-clamp`
+    pub clamp: bool,
+    /// `-imfchan`
+    pub channel: Option<ImageChannel>,
+    /// `-mm`, as `[base, gain]`
+    pub range: [f32; 2],
+    /// `-o`
+    pub offset: [f32; 3],
+    /// `-s`
+    pub scale: [f32; 3],
+    /// `-t`
+    pub turbulence: [f32; 3],
+    /// `-texres`
+    pub resolution: Option<u32>,
+    /// `-type`, the projection of reflection maps
+    pub projection: Option<String>,
+}
+
+impl TextureMap {
+    pub fn new(file: String) -> Self {
+        Self {
+            file,
+            blend_u: true,
+            blend_v: true,
+            bump_multiplier: 1.0,
+            boost: None,
+            color_correction: false,
+            clamp: false,
+            channel: None,
+            range: [0.0, 1.0],
+            offset: [0.0; 3],
+            scale: [1.0; 3],
+            turbulence: [0.0; 3],
+            resolution: None,
+            projection: None,
+        }
+    }
+}
+
+/// Material declared by `newmtl`. Properties absent from the library are
+/// left unset so that renderers can apply their own defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub name: String,
+    /// `Ka`
+    pub ambient: Option<[f32; 3]>,
+    /// `Kd`
+    pub diffuse: Option<[f32; 3]>,
+    /// `Ks`
+    pub specular: Option<[f32; 3]>,
+    /// `Ke`
+    pub emissive: Option<[f32; 3]>,
+    /// `Tf`
+    pub transmission_filter: Option<[f32; 3]>,
+    /// `Ns`
+    pub specular_exponent: Option<f32>,
+    /// `Ni`
+    pub optical_density: Option<f32>,
+    /// `d`, or `1 - Tr`
+    pub dissolve: Option<f32>,
+    /// `illum`
+    pub illumination: Option<u32>,
+    /// `Pr`
+    pub roughness: Option<f32>,
+    /// `Pm`
+    pub metallic: Option<f32>,
+    /// `Ps`
+    pub sheen: Option<f32>,
+    /// `Pc`
+    pub clearcoat_thickness: Option<f32>,
+    /// `Pcr`
+    pub clearcoat_roughness: Option<f32>,
+    /// `aniso`
+    pub anisotropy: Option<f32>,
+    /// `anisor`
+    pub anisotropy_rotation: Option<f32>,
+    pub ambient_map: Option<TextureMap>,
+    pub diffuse_map: Option<TextureMap>,
+    pub specular_map: Option<TextureMap>,
+    pub specular_exponent_map: Option<TextureMap>,
+    pub dissolve_map: Option<TextureMap>,
+    pub emissive_map: Option<TextureMap>,
+    pub bump_map: Option<TextureMap>,
+    pub displacement_map: Option<TextureMap>,
+    pub decal_map: Option<TextureMap>,
+    pub reflection_maps: Vec<TextureMap>,
+    pub roughness_map: Option<TextureMap>,
+    pub metallic_map: Option<TextureMap>,
+    /// `norm`, a tangent space normal map.
+    pub normal_map: Option<TextureMap>,
+}
+
+impl Material {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ambient: None,
+            diffuse: None,
+            specular: None,
+            emissive: None,
+            transmission_filter: None,
+            specular_exponent: None,
+            optical_density: None,
+            dissolve: None,
+            illumination: None,
+            roughness: None,
+            metallic: None,
+            sheen: None,
+            clearcoat_thickness: None,
+            clearcoat_roughness: None,
+            anisotropy: None,
+            anisotropy_rotation: None,
+            ambient_map: None,
+            diffuse_map: None,
+            specular_map: None,
+            specular_exponent_map: None,
+            dissolve_map: None,
+            emissive_map: None,
+            bump_map: None,
+            displacement_map: None,
+            decal_map: None,
+            reflection_maps: vec![],
+            roughness_map: None,
+            metallic_map: None,
+            normal_map: None,
+        }
+    }
+}
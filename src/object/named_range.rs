@@ -0,0 +1,101 @@
+use std::ops::Range;
+use crate::object::{Face, Object};
+
+/// Name given by an `o` or `g` statement to the faces that follow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedRange {
+    pub name: String,
+    pub faces: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Object,
+    Group,
+}
+
+/// Every face sharing an object or group name, possibly spread over several
+/// ranges when the name is used more than once in the file.
+#[derive(Debug)]
+pub struct SubMesh<'a> {
+    pub name: &'a str,
+    pub ranges: Vec<Range<usize>>,
+    object: &'a Object,
+}
+
+impl<'a> SubMesh<'a> {
+    pub fn faces(&self) -> impl Iterator<Item = &'a Face> + '_ {
+        self.ranges
+            .iter()
+            .flat_map(|range| &self.object.faces[range.clone()])
+    }
+}
+
+impl Object {
+    /// Starts a range for the faces of the object `name`.
+    pub fn begin_object(&mut self, name: String) {
+        Self::begin_ranges(&mut self.objects, vec![name], self.faces.len());
+    }
+
+    /// Starts a range for each group the following faces belong to.
+    pub fn begin_groups(&mut self, names: Vec<String>) {
+        Self::begin_ranges(&mut self.groups, names, self.faces.len());
+    }
+
+    pub fn ranges(&self, grouping: Grouping) -> &[NamedRange] {
+        match grouping {
+            Grouping::Object => &self.objects,
+            Grouping::Group => &self.groups,
+        }
+    }
+
+    /// Sub-meshes of each distinct name, in the order they first appear.
+    pub fn sub_meshes(&self, grouping: Grouping) -> Vec<SubMesh<'_>> {
+        let mut sub_meshes: Vec<SubMesh> = vec![];
+        for range in self.ranges(grouping) {
+            match sub_meshes.iter_mut().find(|sub_mesh| sub_mesh.name == range.name) {
+                Some(sub_mesh) => sub_mesh.ranges.push(range.faces.clone()),
+                None => sub_meshes.push(SubMesh {
+                    name: &range.name,
+                    ranges: vec![range.faces.clone()],
+                    object: self,
+                }),
+            }
+        }
+        sub_meshes
+    }
+
+    /// Grows the ranges opened by the latest `o` and `g` statements so that
+    /// they include the last face.
+    pub(super) fn extend_ranges(&mut self) {
+        let len = self.faces.len();
+        for ranges in [&mut self.objects, &mut self.groups] {
+            let Some(start) = ranges.last().map(|range| range.faces.start) else {
+                continue;
+            };
+            ranges.iter_mut()
+                .rev()
+                .take_while(|range| range.faces.start == start)
+                .for_each(|range| range.faces.end = len);
+        }
+    }
+
+    /// Maps face ranges through a face list rebuild, `offsets[i]` being the new
+    /// index of the first face produced from face `i`.
+    pub(super) fn remap_ranges(&mut self, offsets: &[usize]) {
+        for range in self.objects.iter_mut().chain(self.groups.iter_mut()) {
+            range.faces = offsets[range.faces.start]..offsets[range.faces.end];
+        }
+    }
+
+    fn begin_ranges(ranges: &mut Vec<NamedRange>, names: Vec<String>, start: usize) {
+        // Ranges that did not get any face are superseded by the new ones.
+        while ranges.last().is_some_and(|range| range.faces.start == start) {
+            ranges.pop();
+        }
+        ranges.extend(names.into_iter().map(|name| NamedRange {
+            name,
+            faces: start..start,
+        }));
+    }
+}
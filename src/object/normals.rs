@@ -0,0 +1,105 @@
+use ahash::{HashMap, HashMapExt};
+use crate::object::{vector, Face, Object, Vertex, VertexNormal};
+use crate::object::vector::Vector3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalMode {
+    /// One normal per face, giving a faceted look.
+    Flat,
+    /// Normals averaged over the faces sharing a vertex within a smoothing
+    /// group. Faces outside of any group (`s off`) stay flat.
+    Smooth,
+    /// Normals averaged over every face sharing a vertex, ignoring smoothing
+    /// groups, for files that declare none.
+    SmoothAll,
+}
+
+impl NormalMode {
+    /// Group `face` is smoothed within, if any.
+    fn smoothing_key(self, face: &Face) -> Option<u32> {
+        match self {
+            Self::Flat => None,
+            Self::Smooth => face.is_smooth().then_some(face.smoothing_group),
+            Self::SmoothAll => Some(0),
+        }
+    }
+}
+
+impl Object {
+    /// Whether every face corner references a normal.
+    pub fn has_normals(&self) -> bool {
+        self.faces
+            .iter()
+            .flat_map(|face| &face.corners)
+            .all(|corner| corner.normal.is_some())
+    }
+
+    /// Computes normals for the face corners that have none, leaving the ones
+    /// read from the file untouched, even on a face where only some corners
+    /// have one. Smooth normals are weighted by the area of each face and by
+    /// its angle at the shared vertex.
+    pub fn generate_normals(&mut self, mode: NormalMode) {
+        let accumulated = self.accumulate_normals(mode);
+        let mut smooth_indices: HashMap<(usize, u32), usize> = HashMap::new();
+        let vertices = &self.vertices;
+        let normals = &mut self.vertices_normal;
+        for face in self.faces.iter_mut().filter(|face| lacks_normals(face)) {
+            let Some(group) = mode.smoothing_key(face) else {
+                let normal = vector::normalize(face.area_normal(vertices));
+                normals.push(to_vertex_normal(normal));
+                let index = normals.len() - 1;
+                face.corners.iter_mut()
+                    .filter(|corner| corner.normal.is_none())
+                    .for_each(|corner| corner.normal = Some(index));
+                continue;
+            };
+            for corner in face.corners.iter_mut().filter(|corner| corner.normal.is_none()) {
+                let key = (corner.vertex, group);
+                let index = *smooth_indices.entry(key).or_insert_with(|| {
+                    normals.push(to_vertex_normal(vector::normalize(accumulated[&key])));
+                    normals.len() - 1
+                });
+                corner.normal = Some(index);
+            }
+        }
+    }
+
+    /// Sums the weighted normals of the faces `mode` smooths around each
+    /// vertex, keyed by vertex and smoothing group.
+    fn accumulate_normals(&self, mode: NormalMode) -> HashMap<(usize, u32), Vector3> {
+        let mut accumulated: HashMap<(usize, u32), Vector3> = HashMap::new();
+        let smoothed = self.faces
+            .iter()
+            .filter(|face| lacks_normals(face))
+            .filter_map(|face| Some((face, mode.smoothing_key(face)?)));
+        for (face, group) in smoothed {
+            let area_normal = face.area_normal(&self.vertices);
+            for (i, corner) in face.corners.iter().enumerate() {
+                let weight = corner_angle(face, i, &self.vertices);
+                let sum = accumulated
+                    .entry((corner.vertex, group))
+                    .or_insert([0.0; 3]);
+                *sum = vector::add(*sum, vector::scale(area_normal, weight));
+            }
+        }
+        accumulated
+    }
+}
+
+fn lacks_normals(face: &Face) -> bool {
+    face.corners.iter().any(|corner| corner.normal.is_none())
+}
+
+fn corner_angle(face: &Face, i: usize, vertices: &[Vertex]) -> f32 {
+    let len = face.corners.len();
+    let position = |i: usize| vertices[face.corners[i % len].vertex].position();
+    let current = position(i);
+    vector::angle(
+        vector::sub(position(i + len - 1), current),
+        vector::sub(position(i + 1), current),
+    )
+}
+
+fn to_vertex_normal(normal: Vector3) -> VertexNormal {
+    VertexNormal::new(normal[0], normal[1], normal[2])
+}
@@ -0,0 +1,30 @@
+use crate::error::StatementError;
+use crate::object::{resolve_index, Object};
+
+/// Set of vertices declared by `p`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub vertices: Vec<usize>,
+    /// Index in [`Object::materials`] of the material set by `usemtl`.
+    pub material: Option<usize>,
+}
+
+impl Point {
+    pub fn new() -> Self {
+        Point {
+            vertices: vec![],
+            material: None,
+        }
+    }
+
+    pub fn push_arg(&mut self, v: isize, object: &Object) -> Result<(), StatementError> {
+        self.vertices.push(resolve_index(v, object.vertices.len())?);
+        Ok(())
+    }
+}
+
+impl Default for Point {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,43 @@
+pub type Vector3 = [f32; 3];
+
+pub fn add(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: Vector3, factor: f32) -> Vector3 {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+pub fn dot(a: Vector3, b: Vector3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn length(a: Vector3) -> f32 {
+    dot(a, a).sqrt()
+}
+
+/// Returns `a` scaled to unit length, or `a` itself when it is null.
+pub fn normalize(a: Vector3) -> Vector3 {
+    let len = length(a);
+    if len == 0.0 {
+        return a;
+    }
+    scale(a, 1.0 / len)
+}
+
+/// Angle in radians between `a` and `b`.
+pub fn angle(a: Vector3, b: Vector3) -> f32 {
+    dot(normalize(a), normalize(b)).clamp(-1.0, 1.0).acos()
+}
+
+pub fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
@@ -1,14 +1,20 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vertex {
-    x: f32,
-    y: f32,
-    z: f32,
-    w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+    /// RGB colour given after the coordinates, an extension to the format.
+    pub color: Option<[f32; 3]>,
 }
 
 impl Vertex {
     pub fn new(x: f32, y: f32, z: f32, w_opt: Option<f32>) -> Self {
         let w = w_opt.unwrap_or(1.0);
-        Vertex { x, y, z, w }
+        Vertex { x, y, z, w, color: None }
+    }
+
+    pub fn position(&self) -> [f32; 3] {
+        [self.x, self.y, self.z]
     }
 }
@@ -1,8 +1,8 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VertexNormal {
-    i: f32,
-    j: f32,
-    k: f32,
+    pub i: f32,
+    pub j: f32,
+    pub k: f32,
 }
 
 impl VertexNormal {
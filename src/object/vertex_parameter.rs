@@ -0,0 +1,16 @@
+/// Point in the parameter space of a surface, declared by `vp` and used as a
+/// control point of the trimming curves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexParameter {
+    pub u: f32,
+    pub v: Option<f32>,
+    /// Weight of the control point in a rational curve.
+    pub w: f32,
+}
+
+impl VertexParameter {
+    pub fn new(u: f32, v: Option<f32>, w_opt: Option<f32>) -> Self {
+        let w = w_opt.unwrap_or(1.0);
+        VertexParameter { u, v, w }
+    }
+}
@@ -1,8 +1,8 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VertexTexture {
-    u: f32,
-    v: Option<f32>,
-    w: Option<f32>,
+    pub u: f32,
+    pub v: Option<f32>,
+    pub w: Option<f32>,
 }
 
 impl VertexTexture {
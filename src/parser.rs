@@ -1,31 +1,120 @@
 mod token;
+mod state;
 mod statement;
+mod material_library;
+pub(crate) mod reader;
+mod float;
+mod parallel;
+mod options;
 
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Lines};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+pub use options::{ParseMode, ParseOptions, Parsed, DEFAULT_RESOLUTION};
 pub use token::Token;
+use crate::error::{self, ParseError, StatementError};
 use crate::object::Object;
+use crate::parser::options::Diagnostics;
+use crate::parser::state::State;
 use crate::parser::token::RemoveComment;
 
-pub fn parse(path: &str) -> Result<Object, io::Error> {
-    let mut object= Object::new();
-    let lines = file_read_lines(path)?;
+/// Name under which errors in sources that are not files are reported.
+const READER_NAME: &str = "<input>";
 
-    for line in lines.flatten() {
-        line_parse(line, &mut object)?;
-    }
-    Ok(object)
+/// Parses the file at `path`, looking for its material libraries next to it.
+pub fn parse(path: &str) -> Result<Object, ParseError> {
+    parse_with(path, &ParseOptions::default()).map(|parsed| parsed.object)
+}
+
+/// Parses the statements read from `reader`, resolving the paths of the
+/// material libraries against `directory`.
+pub fn parse_reader(reader: impl BufRead, directory: &Path) -> Result<Object, ParseError> {
+    parse_reader_with(reader, directory, &ParseOptions::default()).map(|parsed| parsed.object)
+}
+
+/// Parses statements held in memory, see [`parse_reader`].
+pub fn parse_str(source: &str, directory: &Path) -> Result<Object, ParseError> {
+    parse_str_with(source, directory, &ParseOptions::default()).map(|parsed| parsed.object)
 }
 
-fn file_read_lines(path: &str) -> io::Result<Lines<BufReader<File>>> {
-    let file = File::open(path)?;
-    Ok(BufReader::new(file).lines())
+/// Same as [`parse`], the statements being split between `threads` threads.
+/// The result, errors included, is the one [`parse`] gives.
+pub fn parse_parallel(path: &str, threads: usize) -> Result<Object, ParseError> {
+    let options = ParseOptions {
+        threads,
+        ..ParseOptions::default()
+    };
+    parse_with(path, &options).map(|parsed| parsed.object)
 }
 
-fn line_parse(line: String, object: &mut Object) -> Result<(), io::Error> {
-    let mut tokens = Token::lex(&line);
+/// Same as [`parse_str`], see [`parse_parallel`].
+pub fn parse_str_parallel(source: &str, directory: &Path, threads: usize) -> Result<Object, ParseError> {
+    let options = ParseOptions {
+        threads,
+        ..ParseOptions::default()
+    };
+    parse_str_with(source, directory, &options).map(|parsed| parsed.object)
+}
+
+/// [`parse`] with the given options.
+pub fn parse_with(path: &str, options: &ParseOptions) -> Result<Parsed, ParseError> {
+    let file = File::open(path)
+        .map_err(|e| ParseError::io(path, e))?;
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    parse_source(BufReader::new(file), path, directory, options)
+}
+
+/// [`parse_reader`] with the given options.
+pub fn parse_reader_with(reader: impl BufRead, directory: &Path, options: &ParseOptions) -> Result<Parsed, ParseError> {
+    parse_source(reader, READER_NAME, directory, options)
+}
+
+/// [`parse_str`] with the given options.
+pub fn parse_str_with(source: &str, directory: &Path, options: &ParseOptions) -> Result<Parsed, ParseError> {
+    parse_reader_with(source.as_bytes(), directory, options)
+}
+
+fn parse_source(reader: impl BufRead, name: &str, directory: &Path, options: &ParseOptions) -> Result<Parsed, ParseError> {
+    let mut diagnostics = Diagnostics::new(options.mode);
+    let source = reader::read_source(reader, name, &mut diagnostics)?;
+    let mut object = match options.threads {
+        0 | 1 => statements_parse(&source, name, options, &mut diagnostics)?,
+        _ => parallel::statements_parse(&source, name, options, &mut diagnostics)?,
+    };
+    material_library::load(&mut object, directory, &mut diagnostics)?;
+    Ok(Parsed {
+        object,
+        warnings: diagnostics.warnings,
+    })
+}
+
+fn statements_parse(source: &str, name: &str, options: &ParseOptions, diagnostics: &mut Diagnostics) -> Result<Object, ParseError> {
+    let mut object = Object::new();
+    let mut state = State::new(options.resolution);
+    let mut tokens = vec![];
+    // Statement opening the free-form element in progress, where an element
+    // the file leaves without an `end` is reported.
+    let mut opening = None;
+
+    for line in reader::Lines::new(source) {
+        let open = state.free_form.element.is_some();
+        Token::lex(line.text, &mut tokens);
+        if let Err(e) = line_parse(&mut tokens, &mut object, &mut state) {
+            diagnostics.report(ParseError::syntax(name, line.number, &line.source(), e))?;
+        }
+        if !open && state.free_form.element.is_some() {
+            opening = Some((line.number, line.source().into_owned()));
+        }
+    }
+    if let (Some(_), Some((number, source_line))) = (&state.free_form.element, opening) {
+        diagnostics.report(ParseError::syntax(name, number, &source_line, error::unterminated_free_form_element()))?;
+    }
+    state.finish(&mut object);
+    Ok(object)
+}
 
+fn line_parse(tokens: &mut Vec<Token>, object: &mut Object, state: &mut State) -> Result<(), StatementError> {
     tokens.remove_comment();
-    statement::statement_router(tokens, object)?;
+    statement::statement_router(tokens, object, state)?;
     Ok(())
 }
@@ -0,0 +1,80 @@
+/// Largest mantissa an `f64` holds exactly.
+const MAX_MANTISSA: u64 = 1 << f64::MANTISSA_DIGITS;
+
+/// Powers of ten an `f64` holds exactly.
+const POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11,
+    1e12, 1e13, 1e14, 1e15, 1e16, 1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Bits of an `f64` mantissa that an `f32` drops.
+const TRUNCATED_BITS: u32 = f64::MANTISSA_DIGITS - f32::MANTISSA_DIGITS;
+const TRUNCATED_MASK: u64 = (1 << TRUNCATED_BITS) - 1;
+const HALFWAY: u64 = 1 << (TRUNCATED_BITS - 1);
+
+/// Parses `[+-]digits[.digits][(e|E)[+-]digits]` when both the digits and the
+/// power of ten fit in an `f64`, in which case a single correctly rounded
+/// operation gives the nearest double (Clinger's fast path). Rounding that
+/// double again to an `f32` is exact unless it falls halfway between two
+/// floats. This covers the numbers written by exporters; anything else,
+/// including invalid input, returns `None`.
+pub fn parse_f32(s: &str) -> Option<f32> {
+    let bytes = s.as_bytes();
+    let (negative, mut i) = match bytes.first()? {
+        b'-' => (true, 1),
+        b'+' => (false, 1),
+        _ => (false, 0),
+    };
+    let mut mantissa = 0;
+    let integer_start = i;
+    i = accumulate(bytes, i, &mut mantissa)?;
+    let mut digits = i - integer_start;
+    let mut exponent: i64 = 0;
+    if bytes.get(i) == Some(&b'.') {
+        let fraction_start = i + 1;
+        i = accumulate(bytes, fraction_start, &mut mantissa)?;
+        digits += i - fraction_start;
+        exponent -= (i - fraction_start) as i64;
+    }
+    if digits == 0 {
+        return None;
+    }
+    if let Some(b'e' | b'E') = bytes.get(i) {
+        let (negative_exponent, start) = match bytes.get(i + 1) {
+            Some(b'-') => (true, i + 2),
+            Some(b'+') => (false, i + 2),
+            _ => (false, i + 1),
+        };
+        let mut value = 0;
+        i = accumulate(bytes, start, &mut value)?;
+        if i == start {
+            return None;
+        }
+        let value = i64::try_from(value).ok()?;
+        exponent += if negative_exponent { -value } else { value };
+    }
+    if i != bytes.len() || mantissa > MAX_MANTISSA {
+        return None;
+    }
+    let power = POWERS_OF_TEN.get(usize::try_from(exponent.unsigned_abs()).ok()?)?;
+    let value = match exponent < 0 {
+        true => mantissa as f64 / power,
+        false => mantissa as f64 * power,
+    };
+    let normal = value == 0.0 || (f32::MIN_POSITIVE as f64..=f32::MAX as f64).contains(&value);
+    if !normal || value.to_bits() & TRUNCATED_MASK == HALFWAY {
+        return None;
+    }
+    let value = value as f32;
+    Some(if negative { -value } else { value })
+}
+
+/// Appends the decimal digits starting at `i` to `value`, returning the index
+/// of the first other byte, or `None` on overflow.
+fn accumulate(bytes: &[u8], mut i: usize, value: &mut u64) -> Option<usize> {
+    while let Some(&byte) = bytes.get(i).filter(|byte| byte.is_ascii_digit()) {
+        *value = value.checked_mul(10)?.checked_add(u64::from(byte - b'0'))?;
+        i += 1;
+    }
+    Some(i)
+}
@@ -0,0 +1,167 @@
+mod texture_map;
+
+use std::fs::File;
+use std::path::Path;
+
+use crate::error::{self, ParseError, StatementError};
+use crate::object::{Material, Object, TextureMap};
+use crate::parser::Token;
+use crate::parser::options::Diagnostics;
+use crate::parser::reader::{self, Lines};
+use crate::parser::token::{ParseOptional, RemoveComment};
+
+const NEWMTL_MIN_STATEMENT_LENGTH: usize = 1;
+const COLOR_MIN_STATEMENT_LENGTH: usize = 1;
+const COLOR_MAX_STATEMENT_LENGTH: usize = 3;
+const SCALAR_STATEMENT_LENGTH: usize = 1;
+
+/// Loads the libraries referenced by `object`, resolving their paths against
+/// `directory`. Definitions replace the empty materials that `usemtl`
+/// statements declared.
+pub fn load(object: &mut Object, directory: &Path, diagnostics: &mut Diagnostics) -> Result<(), ParseError> {
+    for library in &object.material_libraries {
+        let path = directory.join(library);
+        let materials = match parse(&path.to_string_lossy(), diagnostics) {
+            Ok(materials) => materials,
+            Err(e) => {
+                diagnostics.report(e)?;
+                continue;
+            }
+        };
+        for material in materials {
+            match object.materials.iter_mut().find(|known| known.name == material.name) {
+                Some(known) => *known = material,
+                None => object.materials.push(material),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn parse(path: &str, diagnostics: &mut Diagnostics) -> Result<Vec<Material>, ParseError> {
+    let mut materials = vec![];
+    let file = File::open(path)
+        .map_err(|e| ParseError::io(path, e))?;
+
+    let source = reader::read_source(file, path, diagnostics)?;
+    let mut tokens = vec![];
+
+    for line in Lines::new(&source) {
+        Token::lex_words(line.text, &mut tokens);
+        if let Err(e) = line_parse(&mut tokens, &mut materials) {
+            diagnostics.report(ParseError::syntax(path, line.number, &line.source(), e))?;
+        }
+    }
+    Ok(materials)
+}
+
+fn line_parse(tokens: &mut Vec<Token>, materials: &mut Vec<Material>) -> Result<(), StatementError> {
+    tokens.remove_comment();
+    let Some((identifier, tokens)) = tokens.split_first() else {
+        return Ok(())
+    };
+    let keyword = identifier.extract_value()?;
+    if keyword == "newmtl" {
+        return newmtl_parse(tokens, materials);
+    }
+    let material = materials.last_mut()
+        .ok_or_else(|| identifier.locate(error::missing_material()))?;
+    match keyword {
+        "Ka" => material.ambient = Some(color_parse(tokens)?),
+        "Kd" => material.diffuse = Some(color_parse(tokens)?),
+        "Ks" => material.specular = Some(color_parse(tokens)?),
+        "Ke" => material.emissive = Some(color_parse(tokens)?),
+        "Tf" => material.transmission_filter = Some(color_parse(tokens)?),
+        "Ns" => material.specular_exponent = Some(scalar_parse(tokens)?),
+        "Ni" => material.optical_density = Some(scalar_parse(tokens)?),
+        "d" => material.dissolve = Some(dissolve_parse(tokens)?),
+        "Tr" => material.dissolve = Some(1.0 - scalar_parse(tokens)?),
+        "illum" => material.illumination = Some(illumination_parse(tokens)?),
+        "Pr" => material.roughness = Some(scalar_parse(tokens)?),
+        "Pm" => material.metallic = Some(scalar_parse(tokens)?),
+        "Ps" => material.sheen = Some(scalar_parse(tokens)?),
+        "Pc" => material.clearcoat_thickness = Some(scalar_parse(tokens)?),
+        "Pcr" => material.clearcoat_roughness = Some(scalar_parse(tokens)?),
+        "aniso" => material.anisotropy = Some(scalar_parse(tokens)?),
+        "anisor" => material.anisotropy_rotation = Some(scalar_parse(tokens)?),
+        "map_Ka" => material.ambient_map = Some(TextureMap::parse(tokens)?),
+        "map_Kd" => material.diffuse_map = Some(TextureMap::parse(tokens)?),
+        "map_Ks" => material.specular_map = Some(TextureMap::parse(tokens)?),
+        "map_Ns" => material.specular_exponent_map = Some(TextureMap::parse(tokens)?),
+        "map_d" => material.dissolve_map = Some(TextureMap::parse(tokens)?),
+        "map_Ke" => material.emissive_map = Some(TextureMap::parse(tokens)?),
+        "bump" | "map_bump" | "map_Bump" => material.bump_map = Some(TextureMap::parse(tokens)?),
+        "disp" => material.displacement_map = Some(TextureMap::parse(tokens)?),
+        "decal" => material.decal_map = Some(TextureMap::parse(tokens)?),
+        "refl" => material.reflection_maps.push(TextureMap::parse(tokens)?),
+        "map_Pr" => material.roughness_map = Some(TextureMap::parse(tokens)?),
+        "map_Pm" => material.metallic_map = Some(TextureMap::parse(tokens)?),
+        "norm" => material.normal_map = Some(TextureMap::parse(tokens)?),
+        _ => return Err(identifier.locate(error::invalid_token(identifier))),
+    }
+    Ok(())
+}
+
+fn newmtl_parse(tokens: &[Token], materials: &mut Vec<Material>) -> Result<(), StatementError> {
+    if tokens.len() < NEWMTL_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {NEWMTL_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    materials.push(Material::new(join_values(tokens)?));
+    Ok(())
+}
+
+/// `r [g b]`, a single component standing for a grey level.
+fn color_parse(tokens: &[Token]) -> Result<[f32; 3], StatementError> {
+    let range = COLOR_MIN_STATEMENT_LENGTH..=COLOR_MAX_STATEMENT_LENGTH;
+    if !range.contains(&tokens.len()) || tokens.len() == 2 {
+        return Err(error::invalid_argument_length(
+            format!("{COLOR_MIN_STATEMENT_LENGTH} or {COLOR_MAX_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let r = tokens[0].parse_f32()?;
+    let g = tokens.get(1).parse_optional_f32()?.unwrap_or(r);
+    let b = tokens.get(2).parse_optional_f32()?.unwrap_or(r);
+    Ok([r, g, b])
+}
+
+fn scalar_parse(tokens: &[Token]) -> Result<f32, StatementError> {
+    if tokens.len() != SCALAR_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!("{SCALAR_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    tokens[0].parse_f32()
+}
+
+/// `d [-halo] factor`, the halo variant being rendered as a plain dissolve.
+fn dissolve_parse(tokens: &[Token]) -> Result<f32, StatementError> {
+    match tokens.first().map(Token::extract_value) {
+        Some(Ok("-halo")) => scalar_parse(&tokens[1..]),
+        _ => scalar_parse(tokens),
+    }
+}
+
+fn illumination_parse(tokens: &[Token]) -> Result<u32, StatementError> {
+    if tokens.len() != SCALAR_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!("{SCALAR_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    tokens[0].extract_value()?
+        .parse()
+        .map_err(|e| tokens[0].locate(error::custom(e)))
+}
+
+/// Rebuilds a name containing spaces out of the tokens it was split into.
+fn join_values(tokens: &[Token]) -> Result<String, StatementError> {
+    let values = tokens.iter()
+        .map(Token::extract_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values.join(" "))
+}
@@ -0,0 +1,99 @@
+use crate::error::{self, StatementError};
+use crate::object::{ImageChannel, TextureMap};
+use crate::parser::Token;
+use super::join_values;
+
+impl TextureMap {
+    /// Parses `[-option args...]... file name`. Options are recognised up to
+    /// the first token that is not one, the rest being the file name, which
+    /// may contain spaces.
+    pub fn parse(tokens: &[Token]) -> Result<Self, StatementError> {
+        let mut map = TextureMap::new(String::new());
+        let mut i = 0;
+        while let Some(option) = tokens.get(i) {
+            let name = option.extract_value()?;
+            if !name.starts_with('-') {
+                break;
+            }
+            let args = &tokens[i + 1..];
+            let consumed = map.option_parse(name, args)
+                .map_err(|e| option.locate(e))?;
+            i += 1 + consumed;
+        }
+        if i == tokens.len() {
+            return Err(error::missing_file_name());
+        }
+        map.file = join_values(&tokens[i..])?;
+        Ok(map)
+    }
+
+    /// Applies one option and returns how many arguments it consumed.
+    fn option_parse(&mut self, name: &str, args: &[Token]) -> Result<usize, StatementError> {
+        match name {
+            "-blendu" => self.blend_u = switch_parse(args)?,
+            "-blendv" => self.blend_v = switch_parse(args)?,
+            "-cc" => self.color_correction = switch_parse(args)?,
+            "-clamp" => self.clamp = switch_parse(args)?,
+            "-bm" => self.bump_multiplier = first_arg(args)?.parse_f32()?,
+            "-boost" => self.boost = Some(first_arg(args)?.parse_f32()?),
+            "-imfchan" => self.channel = Some(channel_parse(args)?),
+            "-texres" => self.resolution = Some(unsigned_parse(args)?),
+            "-type" => self.projection = Some(first_arg(args)?.extract_value()?.to_string()),
+            "-mm" => return numbers_parse(args, &mut self.range),
+            "-o" => return numbers_parse(args, &mut self.offset),
+            "-s" => return numbers_parse(args, &mut self.scale),
+            "-t" => return numbers_parse(args, &mut self.turbulence),
+            _ => return Err(error::unknown_option(name)),
+        }
+        Ok(1)
+    }
+}
+
+fn first_arg<'a, 'b>(args: &'b [Token<'a>]) -> Result<&'b Token<'a>, StatementError> {
+    args.first()
+        .ok_or_else(error::missing_option_argument)
+}
+
+fn switch_parse(args: &[Token]) -> Result<bool, StatementError> {
+    let token = first_arg(args)?;
+    match token.extract_value()? {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(token.locate(error::invalid_token(token))),
+    }
+}
+
+fn channel_parse(args: &[Token]) -> Result<ImageChannel, StatementError> {
+    let token = first_arg(args)?;
+    match token.extract_value()? {
+        "r" => Ok(ImageChannel::Red),
+        "g" => Ok(ImageChannel::Green),
+        "b" => Ok(ImageChannel::Blue),
+        "m" => Ok(ImageChannel::Matte),
+        "l" => Ok(ImageChannel::Luminance),
+        "z" => Ok(ImageChannel::Depth),
+        _ => Err(token.locate(error::invalid_token(token))),
+    }
+}
+
+fn unsigned_parse(args: &[Token]) -> Result<u32, StatementError> {
+    let token = first_arg(args)?;
+    token.extract_value()?
+        .parse()
+        .map_err(|e| token.locate(error::custom(e)))
+}
+
+/// Reads `first [second...]`, up to `N` numbers. Components that are not
+/// given keep their previous value.
+fn numbers_parse<const N: usize>(args: &[Token], res: &mut [f32; N]) -> Result<usize, StatementError> {
+    res[0] = first_arg(args)?.parse_f32()?;
+    let mut count = 1;
+    for token in args.iter().skip(1).take(N - 1) {
+        let Ok(value) = token.parse_f32() else {
+            break;
+        };
+        res[count] = value;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,73 @@
+use crate::error::{ParseError, ParseWarning};
+use crate::object::Object;
+
+/// How the parser reacts to statements it cannot use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Stops on the first error.
+    #[default]
+    Strict,
+    /// Skips unsupported and malformed statements, as well as material
+    /// libraries that cannot be read, reporting each of them as a warning.
+    Lenient,
+}
+
+/// Segments per knot span of the free-form elements, enough for smooth
+/// shading of the usual cubic patches.
+pub const DEFAULT_RESOLUTION: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// Threads parsing the statements, more than one enabling the parallel
+    /// parser.
+    pub threads: usize,
+    /// Segments each knot span of the free-form curves and surfaces is split
+    /// into when tessellated into lines and faces, 0 only keeping them in
+    /// the model.
+    pub resolution: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+            threads: 1,
+            resolution: DEFAULT_RESOLUTION,
+        }
+    }
+}
+
+/// Result of a parse along with the warnings of the lenient mode.
+#[derive(Debug)]
+pub struct Parsed {
+    pub object: Object,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Collects the errors that the mode allows to recover from.
+#[derive(Debug)]
+pub struct Diagnostics {
+    mode: ParseMode,
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl Diagnostics {
+    pub fn new(mode: ParseMode) -> Self {
+        Self {
+            mode,
+            warnings: vec![],
+        }
+    }
+
+    /// Returns `error` in strict mode, records it as a warning otherwise.
+    pub fn report(&mut self, error: ParseError) -> Result<(), ParseError> {
+        match self.mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => {
+                self.warnings.push(ParseWarning(error));
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::panic;
+use std::thread;
+
+use crate::error::ParseError;
+use crate::object::{Face, Object, Vertex, VertexNormal, VertexTexture};
+use crate::parser::options::{Diagnostics, ParseOptions};
+use crate::parser::reader::Lines;
+use crate::parser::state::State;
+use crate::parser::statement::CornerIndices;
+use crate::parser::token::RemoveComment;
+use crate::parser::{self, line_parse, Token};
+
+/// Number of attributes a chunk has parsed at some point of its statements.
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    vertices: usize,
+    vertices_texture: usize,
+    vertices_normal: usize,
+}
+
+/// Statement whose effect depends on the ones preceding it in the file.
+enum Deferred<'a> {
+    /// Face whose indices are resolved once the attributes of the previous
+    /// chunks are known.
+    Face(Vec<CornerIndices>),
+    /// Any other statement, replayed as is.
+    Statement(&'a str),
+}
+
+/// Result of the first pass over a part of the source: the attributes, which
+/// are independent of the rest of the file, and the statements to replay in
+/// order in the second pass.
+struct Chunk<'a> {
+    attributes: Object,
+    deferred: Vec<(Counts, Deferred<'a>)>,
+}
+
+/// Parses `source` in two passes. The first one splits it in one chunk of
+/// whole statements per thread and parses their attributes and face corners
+/// in parallel. The second one merges the chunks in order, resolving face
+/// indices, relative ones included, and replaying the other statements.
+///
+/// The result is the one of the sequential parser, which parses the source
+/// again to report errors exactly as it would, or recover from them.
+pub fn statements_parse(source: &str, name: &str, options: &ParseOptions, diagnostics: &mut Diagnostics) -> Result<Object, ParseError> {
+    let chunks = thread::scope(|scope| {
+        let handles: Vec<_> = split(source, options.threads.max(1))
+            .into_iter()
+            .map(|part| scope.spawn(|| chunk_parse(part)))
+            .collect();
+        handles.into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect::<Option<Vec<_>>>()
+    });
+    match chunks.and_then(|chunks| merge(chunks, options.resolution)) {
+        Some(object) => Ok(object),
+        None => parser::statements_parse(source, name, options, diagnostics),
+    }
+}
+
+/// Splits `source` in about `count` parts ending on a statement boundary.
+fn split(source: &str, count: usize) -> Vec<&str> {
+    let size = source.len().div_ceil(count).max(1);
+    let mut parts = vec![];
+    let mut rest = source;
+    while !rest.is_empty() {
+        let end = statement_end(rest, size.min(rest.len()));
+        let (part, next) = rest.split_at(end);
+        parts.push(part);
+        rest = next;
+    }
+    parts
+}
+
+/// Index following the first line terminator at or after `from` that does
+/// not continue the statement.
+fn statement_end(source: &str, mut from: usize) -> usize {
+    let bytes = source.as_bytes();
+    while let Some(offset) = bytes[from..].iter().position(|&byte| byte == b'\n') {
+        let newline = from + offset;
+        let text = &source[..newline];
+        if !text.strip_suffix('\r').unwrap_or(text).ends_with('\\') {
+            return newline + 1;
+        }
+        from = newline + 1;
+    }
+    source.len()
+}
+
+/// First pass over a chunk, giving up on the first error.
+fn chunk_parse(source: &str) -> Option<Chunk<'_>> {
+    let mut chunk = Chunk {
+        attributes: Object::new(),
+        deferred: vec![],
+    };
+    let mut tokens = vec![];
+    for line in Lines::new(source) {
+        Token::lex(line.text, &mut tokens);
+        tokens.remove_comment();
+        let attributes = &mut chunk.attributes;
+        match tokens.split_first() {
+            None => (),
+            Some((Token::Vertex, arguments)) => Vertex::parse(arguments, attributes).ok()?,
+            Some((Token::VertexTexture, arguments)) => VertexTexture::parse(arguments, attributes).ok()?,
+            Some((Token::VertexNormal, arguments)) => VertexNormal::parse(arguments, attributes).ok()?,
+            Some((Token::Face, arguments)) => {
+                let indices = Face::indices_parse(arguments).ok()?;
+                chunk.deferred.push((chunk.counts(), Deferred::Face(indices)));
+            }
+            Some(_) => chunk.deferred.push((chunk.counts(), Deferred::Statement(line.text))),
+        }
+    }
+    Some(chunk)
+}
+
+/// Second pass, giving up on the first error.
+fn merge(chunks: Vec<Chunk>, resolution: usize) -> Option<Object> {
+    let mut object = Object::new();
+    let mut state = State::new(resolution);
+    let mut tokens = vec![];
+    for chunk in chunks {
+        let mut appended = Counts::default();
+        for (counts, deferred) in chunk.deferred {
+            chunk.attributes.append_to(&mut object, appended, counts);
+            appended = counts;
+            match deferred {
+                Deferred::Face(indices) => {
+                    let face = Face::resolve(&indices, &object, &state).ok()?;
+                    object.add_face(face);
+                }
+                Deferred::Statement(text) => {
+                    Token::lex(text, &mut tokens);
+                    line_parse(&mut tokens, &mut object, &mut state).ok()?;
+                }
+            }
+        }
+        chunk.attributes.append_to(&mut object, appended, chunk.attributes.counts());
+    }
+    if state.free_form.element.is_some() {
+        return None;
+    }
+    state.finish(&mut object);
+    Some(object)
+}
+
+impl Chunk<'_> {
+    fn counts(&self) -> Counts {
+        self.attributes.counts()
+    }
+}
+
+impl Object {
+    fn counts(&self) -> Counts {
+        Counts {
+            vertices: self.vertices.len(),
+            vertices_texture: self.vertices_texture.len(),
+            vertices_normal: self.vertices_normal.len(),
+        }
+    }
+
+    /// Appends the attributes parsed between two points of the chunk.
+    fn append_to(&self, object: &mut Object, from: Counts, to: Counts) {
+        object.vertices.extend_from_slice(&self.vertices[from.vertices..to.vertices]);
+        object.vertices_texture.extend_from_slice(&self.vertices_texture[from.vertices_texture..to.vertices_texture]);
+        object.vertices_normal.extend_from_slice(&self.vertices_normal[from.vertices_normal..to.vertices_normal]);
+    }
+}
@@ -0,0 +1,126 @@
+use std::borrow::Cow;
+use std::io::Read;
+use std::str;
+use std::string::FromUtf8Error;
+
+use crate::error::{self, ParseError};
+use crate::parser::options::Diagnostics;
+
+const BYTE_ORDER_MARK: &[u8] = b"\xEF\xBB\xBF";
+const CONTINUATION: char = '\\';
+
+/// Reads the whole of `reader`, dropping the byte order mark. Input that is
+/// not UTF-8 is reported where it first occurs, and replaced by U+FFFD when
+/// the diagnostics recover from it.
+pub fn read_source(mut reader: impl Read, name: &str, diagnostics: &mut Diagnostics) -> Result<String, ParseError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)
+        .map_err(|e| ParseError::io(name, e))?;
+    if bytes.starts_with(BYTE_ORDER_MARK) {
+        bytes.drain(..BYTE_ORDER_MARK.len());
+    }
+    let error = match String::from_utf8(bytes) {
+        Ok(source) => return Ok(source),
+        Err(error) => error,
+    };
+    diagnostics.report(utf8_error(&error, name))?;
+    Ok(String::from_utf8_lossy(error.as_bytes()).into_owned())
+}
+
+fn utf8_error(e: &FromUtf8Error, name: &str) -> ParseError {
+    let bytes = e.as_bytes();
+    let valid = &bytes[..e.utf8_error().valid_up_to()];
+    let line_start = valid.iter().rposition(|&byte| byte == b'\n').map_or(0, |i| i + 1);
+    let line_end = bytes[line_start..].iter()
+        .position(|&byte| byte == b'\n')
+        .map_or(bytes.len(), |i| line_start + i);
+    let line = bytes[..line_start].iter().filter(|&&byte| byte == b'\n').count() + 1;
+    let column = String::from_utf8_lossy(&valid[line_start..]).chars().count() + 1;
+    let source_line = String::from_utf8_lossy(&bytes[line_start..line_end]);
+    let source_line = source_line.strip_suffix('\r').unwrap_or(&source_line);
+    ParseError::syntax(name, line, source_line, error::invalid_utf8().at(column))
+}
+
+/// Statement of the source, spanning several physical lines when they end
+/// with a backslash.
+pub struct Line<'a> {
+    /// 1-based number of the first physical line of the statement.
+    pub number: usize,
+    /// Text of the statement without its terminator. Continuations are kept
+    /// and lexed as whitespace.
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Text to quote in diagnostics, continuations being replaced by as many
+    /// spaces so that columns still line up.
+    pub fn source(&self) -> Cow<'a, str> {
+        if !self.text.contains('\n') {
+            return Cow::Borrowed(self.text);
+        }
+        let mut source = String::with_capacity(self.text.len());
+        let mut chars = self.text.chars().peekable();
+        while let Some(c) = chars.next() {
+            let continuation = c == CONTINUATION && matches!(chars.peek(), Some('\r' | '\n'));
+            source.push(if continuation || c == '\r' || c == '\n' { ' ' } else { c });
+        }
+        Cow::Owned(source)
+    }
+}
+
+/// Iterates over the statements of a source, handling `\n` and `\r\n`
+/// terminators alike.
+pub struct Lines<'a> {
+    source: &'a str,
+    number: usize,
+    /// Whether a backslash at the end of a line continues the statement.
+    continuations: bool,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            number: 0,
+            continuations: true,
+        }
+    }
+
+    /// Lines of a format without continuations, such as PLY headers and
+    /// ASCII STL, where a trailing backslash belongs to the line.
+    pub fn plain(source: &'a str) -> Self {
+        Self {
+            continuations: false,
+            ..Self::new(source)
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.source.is_empty() {
+            return None;
+        }
+        let number = self.number + 1;
+        let mut end = 0;
+        let (text, rest) = loop {
+            self.number += 1;
+            let Some(newline) = self.source[end..].find('\n').map(|i| end + i) else {
+                break (self.source, "");
+            };
+            let text = &self.source[..newline];
+            let text = text.strip_suffix('\r').unwrap_or(text);
+            if !self.continuations || !text.ends_with(CONTINUATION) || newline + 1 == self.source.len() {
+                break (text, &self.source[newline + 1..]);
+            }
+            end = newline + 1;
+        };
+        self.source = rest;
+        Some(Line {
+            number,
+            text: text.strip_suffix('\r').unwrap_or(text),
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{self, StatementError};
+use crate::object::{Corner, Curve, Curve2, CurveType, FreeFormAttributes, Object, Surface};
+
+/// Attributes set by a statement and applied to every element that follows it.
+#[derive(Debug, Default)]
+pub struct State {
+    pub smoothing_group: u32,
+    pub material: Option<usize>,
+    pub free_form: FreeFormState,
+    /// Segments each knot span of the free-form elements is split into when
+    /// tessellated, 0 leaving them as they are.
+    pub resolution: usize,
+    pub tessellation: Tessellation,
+}
+
+/// Attributes set by `cstype`, `deg`, `bmat` and `step`, and the element
+/// opened by `curv`, `curv2` or `surf` until its `end`.
+#[derive(Debug, Default)]
+pub struct FreeFormState {
+    pub curve_type: Option<CurveType>,
+    pub rational: bool,
+    pub degree: [usize; 2],
+    pub basis_matrices: [Vec<f32>; 2],
+    pub step: [usize; 2],
+    pub element: Option<FreeFormElement>,
+}
+
+#[derive(Debug)]
+pub enum FreeFormElement {
+    Curve(Curve),
+    Curve2(Curve2),
+    Surface(Surface),
+}
+
+/// Geometry tessellated from the free-form elements. Its attributes only join
+/// the ones of the object once the whole file is parsed, so that they do not
+/// shift the indices of the statements that follow.
+#[derive(Debug, Default)]
+pub struct Tessellation {
+    pub mesh: Object,
+    /// Faces and lines of the object whose corners index the mesh.
+    pub faces: Vec<usize>,
+    pub lines: Vec<usize>,
+}
+
+impl State {
+    pub fn new(resolution: usize) -> Self {
+        Self {
+            resolution,
+            ..Self::default()
+        }
+    }
+
+    /// Appends the tessellated geometry to `object`, pointing the corners
+    /// referencing it to its new place.
+    pub fn finish(self, object: &mut Object) {
+        let Tessellation { mesh, faces, lines } = self.tessellation;
+        let vertices = object.vertices.len();
+        let vertices_texture = object.vertices_texture.len();
+        let vertices_normal = object.vertices_normal.len();
+        let shift = |corners: &mut [Corner]| {
+            for corner in corners {
+                corner.vertex += vertices;
+                corner.texture = corner.texture.map(|index| index + vertices_texture);
+                corner.normal = corner.normal.map(|index| index + vertices_normal);
+            }
+        };
+        faces.into_iter().for_each(|index| shift(&mut object.faces[index].corners));
+        lines.into_iter().for_each(|index| shift(&mut object.lines[index].corners));
+        object.vertices.extend(mesh.vertices);
+        object.vertices_texture.extend(mesh.vertices_texture);
+        object.vertices_normal.extend(mesh.vertices_normal);
+    }
+}
+
+impl FreeFormState {
+    /// Attributes of an element declared now.
+    pub fn attributes(&self) -> Result<FreeFormAttributes, StatementError> {
+        Ok(FreeFormAttributes {
+            curve_type: self.curve_type.ok_or_else(error::missing_curve_type)?,
+            rational: self.rational,
+            degree: self.degree,
+            basis_matrices: self.basis_matrices.clone(),
+            step: self.step,
+        })
+    }
+}
@@ -1,28 +1,45 @@
 mod vertex_data;
 mod elements;
+mod grouping;
+mod material;
+mod free_form;
 
-use std::io;
+pub use elements::CornerIndices;
 
-use crate::error;
-use crate::object::{Object, Vertex, VertexTexture, VertexNormal, Face};
+use crate::error::{self, StatementError};
+use crate::object::{Object, Vertex, VertexTexture, VertexNormal, VertexParameter, Face, Line, Point};
 use crate::parser::Token;
+use crate::parser::state::State;
 
-pub fn statement_router(mut tokens: Vec<Token>, object: &mut Object) -> Result<(), io::Error> {
-    if tokens.is_empty() {
+pub fn statement_router(tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    let Some((identifier, tokens)) = tokens.split_first() else {
         return Ok(())
-    }
-    let identifier = tokens.remove(0);
+    };
     match identifier {
-        Token::Value(_) => Err(error::invalid_token(&identifier)),
+        Token::Value { .. } => Err(identifier.locate(error::invalid_token(identifier))),
         Token::Vertex => Vertex::parse(tokens, object),
         Token::VertexTexture => VertexTexture::parse(tokens, object),
         Token::VertexNormal => VertexNormal::parse(tokens, object),
-        Token::Face => Face::parse(tokens, object),
-        Token::SmoothingGroup => Ok(()),
-        Token::ObjectName => Ok(()),
-        Token::MaterailName => Ok(()),
-        Token::MaterialLibrary => Ok(()),
-        _ => Err(error::unexpected_token(&identifier)),
+        Token::VertexParameter => VertexParameter::parse(tokens, object),
+        Token::Face => Face::parse(tokens, object, state),
+        Token::Line => Line::parse(tokens, object, state),
+        Token::Point => Point::parse(tokens, object, state),
+        Token::SmoothingGroup => grouping::smoothing_group_parse(tokens, state),
+        Token::ObjectName => grouping::object_name_parse(tokens, object),
+        Token::GroupName => grouping::group_name_parse(tokens, object),
+        Token::MaterailName => material::material_name_parse(tokens, object, state),
+        Token::MaterialLibrary => material::material_library_parse(tokens, object),
+        Token::CurveType => free_form::curve_type_parse(tokens, state),
+        Token::Degree => free_form::degree_parse(tokens, state),
+        Token::BasisMatrix => free_form::basis_matrix_parse(tokens, state),
+        Token::Step => free_form::step_parse(tokens, state),
+        Token::Curve => free_form::curve_parse(tokens, object, state),
+        Token::Curve2 => free_form::curve2_parse(tokens, object, state),
+        Token::Surface => free_form::surface_parse(tokens, object, state),
+        Token::Parameter => free_form::parameter_parse(identifier, tokens, state),
+        Token::Trim | Token::Hole => free_form::trim_parse(identifier, tokens, object, state),
+        Token::End => free_form::end_parse(identifier, tokens, object, state),
+        _ => Err(error::unexpected_token(identifier)),
     }
 }
 
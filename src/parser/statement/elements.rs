@@ -1,70 +1,179 @@
-use std::io;
-use crate::error;
-use crate::object::{Object, Face};
+use crate::error::{self, StatementError};
+use crate::object::{Object, Face, Line, Point};
 use crate::parser::Token;
+use crate::parser::state::State;
 
 const F_MIN_STATEMENT_LENGTH: usize = 3;
 const F_MAX_PARAMETER_LENGTH: usize = 3;
 const F_MIN_PARAMETER_LENGTH: usize = 1;
+const L_MIN_STATEMENT_LENGTH: usize = 2;
+const L_MAX_PARAMETER_LENGTH: usize = 2;
+const L_MIN_PARAMETER_LENGTH: usize = 1;
+const P_MIN_STATEMENT_LENGTH: usize = 1;
+
+/// Indices of a face corner as written, `(v, vt, vn)`.
+pub type CornerIndices = (isize, Option<isize>, Option<isize>);
 
 impl Face {
-    pub fn parse(tokens: Vec<Token>, object: & mut Object) -> Result<(), io::Error> {
+    pub fn parse(tokens: &[Token], object: & mut Object, state: &State) -> Result<(), StatementError> {
         let mut face = Self::new();
+        face.smoothing_group = state.smoothing_group;
+        face.material = state.material;
+        Self::length_check(tokens)?;
+        for token in tokens {
+            let (v, vt, vn) = Self::argument_parse(token)
+                .map_err(|e| token.locate(e))?;
+            face.corner_push(v, vt, vn, object)
+                .map_err(|e| token.locate(e))?;
+        }
+        object.add_face(face);
+        Ok(())
+    }
+
+    /// Parses the corners of a face without resolving their indices, which
+    /// [`Face::resolve`] does once the attributes preceding it are known.
+    pub fn indices_parse(tokens: &[Token]) -> Result<Vec<CornerIndices>, StatementError> {
+        Self::length_check(tokens)?;
+        tokens.iter()
+            .map(|token| Self::argument_parse(token).map_err(|e| token.locate(e)))
+            .collect()
+    }
+
+    pub fn resolve(indices: &[CornerIndices], object: &Object, state: &State) -> Result<Self, StatementError> {
+        let mut face = Self::new();
+        face.smoothing_group = state.smoothing_group;
+        face.material = state.material;
+        for &(v, vt, vn) in indices {
+            face.corner_push(v, vt, vn, object)?;
+        }
+        Ok(face)
+    }
+
+    fn length_check(tokens: &[Token]) -> Result<(), StatementError> {
         if tokens.len() < F_MIN_STATEMENT_LENGTH {
             return Err(error::invalid_argument_length(
                 format!(">= {F_MIN_STATEMENT_LENGTH}"),
                 tokens.len(),
             ))
         }
-        for token in tokens {
-            face.argument_parse(token, object)?;
-        }
-        //TODO: Check if the vectors are of the same len
-        object.add_face(face);
         Ok(())
     }
 
-    fn argument_parse(&mut self, token: Token, object: & mut Object) -> Result<(), io::Error> {
-        let parameters: Vec<String> = token.extract_value()?
-            .split('/')
-            .map(|s| s.to_string())
-            .collect();
+    pub(super) fn argument_parse(token: &Token) -> Result<CornerIndices, StatementError> {
+        let value = token.extract_value()?;
         let range = F_MIN_PARAMETER_LENGTH..=F_MAX_PARAMETER_LENGTH;
-        if !range.contains(&parameters.len()) {
+        let length = parameter_length(value);
+        if !range.contains(&length) {
             return Err(error::invalid_argument_length(
                 format!("{F_MIN_PARAMETER_LENGTH}..{F_MAX_PARAMETER_LENGTH}"),
-                parameters.len(),
+                length,
             ))
         }
-        let v = parse_usize(&parameters[0])?;
-        let vt = parse_optional_usize(parameters.get(1))?;
-        let vn = parse_optional_usize(parameters.get(2))?;
+        let mut parameters = value.split('/');
+        let v = parse_optional_index(parameters.next())?
+            .ok_or_else(error::invalid_statement)?;
+        let vt = parse_optional_index(parameters.next())?;
+        let vn = parse_optional_index(parameters.next())?;
+        Ok((v, vt, vn))
+    }
+
+    fn corner_push(&mut self, v: isize, vt: Option<isize>, vn: Option<isize>, object: &Object) -> Result<(), StatementError> {
         self.push_arg(v, vt, vn, object)?;
-        self.check_vector_len()?;
+        self.check_corners()
+    }
+
+    /// Every corner of a face must reference the same kinds of attributes.
+    fn check_corners(&self) -> Result<(), StatementError> {
+        let (first, last) = (self.corners[0], self.corners[self.corners.len() - 1]);
+        if first.texture.is_some() != last.texture.is_some()
+            || first.normal.is_some() != last.normal.is_some() {
+            return Err(error::invalid_statement());
+        }
         Ok(())
     }
+}
 
-    fn check_vector_len(&self) -> Result<(), io::Error> {
-        let v_len = self.vertices.len();
-        let vt_len = self.vertices_texture.len();
-        let vn_len = self.vertices_normal.len();
-        if (vt_len != v_len && vt_len != 0) || (vn_len != v_len && vn_len != 0) {
+impl Line {
+    pub fn parse(tokens: &[Token], object: &mut Object, state: &State) -> Result<(), StatementError> {
+        let mut line = Self::new();
+        line.material = state.material;
+        if tokens.len() < L_MIN_STATEMENT_LENGTH {
+            return Err(error::invalid_argument_length(
+                format!(">= {L_MIN_STATEMENT_LENGTH}"),
+                tokens.len(),
+            ))
+        }
+        for token in tokens {
+            line.argument_parse(token, object)
+                .map_err(|e| token.locate(e))?;
+        }
+        object.add_line(line);
+        Ok(())
+    }
+
+    fn argument_parse(&mut self, token: &Token, object: &mut Object) -> Result<(), StatementError> {
+        let value = token.extract_value()?;
+        let range = L_MIN_PARAMETER_LENGTH..=L_MAX_PARAMETER_LENGTH;
+        let length = parameter_length(value);
+        if !range.contains(&length) {
+            return Err(error::invalid_argument_length(
+                format!("{L_MIN_PARAMETER_LENGTH}..{L_MAX_PARAMETER_LENGTH}"),
+                length,
+            ))
+        }
+        let mut parameters = value.split('/');
+        let v = parse_optional_index(parameters.next())?
+            .ok_or_else(error::invalid_statement)?;
+        let vt = parse_optional_index(parameters.next())?;
+        self.push_arg(v, vt, object)?;
+        let (first, last) = (self.corners[0], self.corners[self.corners.len() - 1]);
+        if first.texture.is_some() != last.texture.is_some() {
             return Err(error::invalid_statement());
         }
         Ok(())
     }
 }
 
-fn parse_usize(str: &String) -> Result<Option<usize>, io::Error> {
+impl Point {
+    pub fn parse(tokens: &[Token], object: &mut Object, state: &State) -> Result<(), StatementError> {
+        let mut point = Self::new();
+        point.material = state.material;
+        if tokens.len() < P_MIN_STATEMENT_LENGTH {
+            return Err(error::invalid_argument_length(
+                format!(">= {P_MIN_STATEMENT_LENGTH}"),
+                tokens.len(),
+            ))
+        }
+        for token in tokens {
+            point.argument_parse(token, object)
+                .map_err(|e| token.locate(e))?;
+        }
+        object.add_point(point);
+        Ok(())
+    }
+
+    fn argument_parse(&mut self, token: &Token, object: &mut Object) -> Result<(), StatementError> {
+        let v = parse_index(token.extract_value()?)?
+            .ok_or_else(error::invalid_statement)?;
+        self.push_arg(v, object)
+    }
+}
+
+/// Number of `/` separated parameters in a corner.
+fn parameter_length(value: &str) -> usize {
+    value.bytes().filter(|&byte| byte == b'/').count() + 1
+}
+
+pub(super) fn parse_index(str: &str) -> Result<Option<isize>, StatementError> {
     match str.is_empty() {
         true => Ok(None),
         false => Ok(Some(str.parse().map_err(error::custom)?))
     }
 }
 
-fn parse_optional_usize(opt: Option<&String>) -> Result<Option<usize>, io::Error> {
+fn parse_optional_index(opt: Option<&str>) -> Result<Option<isize>, StatementError> {
     let res = match opt {
-        Some(str) => parse_usize(str)?,
+        Some(str) => parse_index(str)?,
         None => None,
     };
     Ok(res)
@@ -0,0 +1,279 @@
+use crate::error::{self, StatementError};
+use crate::object::{Curve, Curve2, CurveType, Face, Object, Surface, TrimCurve, U, V};
+use crate::parser::Token;
+use crate::parser::state::{FreeFormElement, State};
+use crate::parser::statement::elements::parse_index;
+
+const CSTYPE_MIN_STATEMENT_LENGTH: usize = 1;
+const CSTYPE_MAX_STATEMENT_LENGTH: usize = 2;
+const DEG_MIN_STATEMENT_LENGTH: usize = 1;
+const DEG_MAX_STATEMENT_LENGTH: usize = 2;
+const BMAT_MIN_STATEMENT_LENGTH: usize = 2;
+const STEP_MIN_STATEMENT_LENGTH: usize = 1;
+const STEP_MAX_STATEMENT_LENGTH: usize = 2;
+const CURV_MIN_STATEMENT_LENGTH: usize = 4;
+const CURV2_MIN_STATEMENT_LENGTH: usize = 2;
+const SURF_MIN_STATEMENT_LENGTH: usize = 5;
+const PARM_MIN_STATEMENT_LENGTH: usize = 3;
+const TRIM_PARAMETER_LENGTH: usize = 3;
+const END_STATEMENT_LENGTH: usize = 0;
+
+/// `[rat] type`, the type being one of `bmatrix`, `bezier`, `bspline`,
+/// `cardinal` and `taylor`.
+pub fn curve_type_parse(tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    length_check(tokens, CSTYPE_MIN_STATEMENT_LENGTH, CSTYPE_MAX_STATEMENT_LENGTH)?;
+    let rational = tokens.len() == CSTYPE_MAX_STATEMENT_LENGTH;
+    if rational && tokens[0].extract_value()? != "rat" {
+        return Err(tokens[0].locate(error::invalid_token(&tokens[0])));
+    }
+    let token = &tokens[tokens.len() - 1];
+    let curve_type = match token.extract_value()? {
+        "bmatrix" => CurveType::BasisMatrix,
+        "bezier" => CurveType::Bezier,
+        "bspline" => CurveType::BSpline,
+        "cardinal" => CurveType::Cardinal,
+        "taylor" => CurveType::Taylor,
+        _ => return Err(token.locate(error::invalid_token(token))),
+    };
+    state.free_form.curve_type = Some(curve_type);
+    state.free_form.rational = rational;
+    Ok(())
+}
+
+/// `degu [degv]`, the v degree only applying to surfaces.
+pub fn degree_parse(tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    length_check(tokens, DEG_MIN_STATEMENT_LENGTH, DEG_MAX_STATEMENT_LENGTH)?;
+    let u = unsigned_parse(&tokens[0])?;
+    let v = tokens.get(1).map(unsigned_parse).transpose()?;
+    state.free_form.degree = [u, v.unwrap_or(0)];
+    Ok(())
+}
+
+/// `u|v matrix`, see [`FreeFormAttributes::basis_matrices`](crate::object::FreeFormAttributes::basis_matrices).
+pub fn basis_matrix_parse(tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    if tokens.len() < BMAT_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {BMAT_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let direction = direction_parse(&tokens[0])?;
+    state.free_form.basis_matrices[direction] = numbers_parse(&tokens[1..])?;
+    Ok(())
+}
+
+/// `stepu [stepv]`.
+pub fn step_parse(tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    length_check(tokens, STEP_MIN_STATEMENT_LENGTH, STEP_MAX_STATEMENT_LENGTH)?;
+    let u = unsigned_parse(&tokens[0])?;
+    let v = tokens.get(1).map(unsigned_parse).transpose()?;
+    state.free_form.step = [u, v.unwrap_or(0)];
+    Ok(())
+}
+
+/// `u0 u1 v1 v2 ...`, opening a curve until `end`.
+pub fn curve_parse(tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    element_check(state)?;
+    if tokens.len() < CURV_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {CURV_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let range = range_parse(&tokens[0], &tokens[1])?;
+    let mut curve = Curve::new(range, state.free_form.attributes()?);
+    curve.material = state.material;
+    for token in &tokens[2..] {
+        curve.push_arg(index_parse(token)?, object)
+            .map_err(|e| token.locate(e))?;
+    }
+    state.free_form.element = Some(FreeFormElement::Curve(curve));
+    Ok(())
+}
+
+/// `vp1 vp2 ...`, opening a trimming curve until `end`.
+pub fn curve2_parse(tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    element_check(state)?;
+    if tokens.len() < CURV2_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {CURV2_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let mut curve = Curve2::new(state.free_form.attributes()?);
+    for token in tokens {
+        curve.push_arg(index_parse(token)?, object)
+            .map_err(|e| token.locate(e))?;
+    }
+    state.free_form.element = Some(FreeFormElement::Curve2(curve));
+    Ok(())
+}
+
+/// `s0 s1 t0 t1 v1/vt1/vn1 ...`, opening a surface until `end`.
+pub fn surface_parse(tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    element_check(state)?;
+    if tokens.len() < SURF_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {SURF_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let range = [range_parse(&tokens[0], &tokens[1])?, range_parse(&tokens[2], &tokens[3])?];
+    let mut surface = Surface::new(range, state.free_form.attributes()?);
+    surface.smoothing_group = state.smoothing_group;
+    surface.material = state.material;
+    for token in &tokens[4..] {
+        let (v, vt, vn) = Face::argument_parse(token)
+            .map_err(|e| token.locate(e))?;
+        surface.push_arg(v, vt, vn, object)
+            .map_err(|e| token.locate(e))?;
+    }
+    state.free_form.element = Some(FreeFormElement::Surface(surface));
+    Ok(())
+}
+
+/// `u|v p1 p2 ...`, the global parameters of the open element.
+pub fn parameter_parse(identifier: &Token, tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    if tokens.len() < PARM_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {PARM_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let direction = direction_parse(&tokens[0])?;
+    let parameters = numbers_parse(&tokens[1..])?;
+    let destination = match (&mut state.free_form.element, direction) {
+        (Some(FreeFormElement::Curve(curve)), U) => &mut curve.parameters,
+        (Some(FreeFormElement::Curve2(curve)), U) => &mut curve.parameters,
+        (Some(FreeFormElement::Surface(surface)), _) => &mut surface.parameters[direction],
+        (Some(_), _) => return Err(tokens[0].locate(error::invalid_token(&tokens[0]))),
+        (None, _) => return Err(error::misplaced_statement(identifier)),
+    };
+    *destination = parameters;
+    Ok(())
+}
+
+/// `u0 u1 curv2d ...`, a loop of trimming curves of the open surface, which
+/// `hole` cuts out and `trim` keeps.
+pub fn trim_parse(identifier: &Token, tokens: &[Token], object: &Object, state: &mut State) -> Result<(), StatementError> {
+    let Some(FreeFormElement::Surface(surface)) = &mut state.free_form.element else {
+        return Err(error::misplaced_statement(identifier));
+    };
+    if tokens.is_empty() || !tokens.len().is_multiple_of(TRIM_PARAMETER_LENGTH) {
+        return Err(error::invalid_argument_length(
+            format!("a multiple of {TRIM_PARAMETER_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let mut trim_loop = vec![];
+    for curve in tokens.chunks(TRIM_PARAMETER_LENGTH) {
+        let range = range_parse(&curve[0], &curve[1])?;
+        let trim = TrimCurve::new(range, index_parse(&curve[2])?, object)
+            .map_err(|e| curve[2].locate(e))?;
+        trim_loop.push(trim);
+    }
+    match identifier {
+        Token::Hole => surface.holes.push(trim_loop),
+        _ => surface.trims.push(trim_loop),
+    }
+    Ok(())
+}
+
+/// Closes the open element, tessellating curves into lines and surfaces into
+/// faces.
+pub fn end_parse(identifier: &Token, tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    if tokens.len() != END_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!("{END_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let element = state.free_form.element.take()
+        .ok_or_else(|| error::misplaced_statement(identifier))?;
+    let resolution = state.resolution;
+    let tessellation = &mut state.tessellation;
+    match element {
+        FreeFormElement::Curve(curve) => {
+            if resolution > 0 {
+                let line = curve.tessellate(object, resolution, &mut tessellation.mesh)?;
+                tessellation.lines.push(object.lines.len());
+                object.add_line(line);
+            } else {
+                curve.check()?;
+            }
+            object.add_curve(curve);
+        }
+        FreeFormElement::Curve2(curve) => {
+            curve.check()?;
+            object.add_curve2(curve);
+        }
+        FreeFormElement::Surface(surface) => {
+            if resolution > 0 {
+                for face in surface.tessellate(object, resolution, &mut tessellation.mesh)? {
+                    tessellation.faces.push(object.faces.len());
+                    object.add_face(face);
+                }
+            } else {
+                surface.check()?;
+            }
+            object.add_surface(surface);
+        }
+    }
+    Ok(())
+}
+
+/// Elements cannot nest, each one needing an `end` before the next.
+fn element_check(state: &State) -> Result<(), StatementError> {
+    match state.free_form.element {
+        Some(_) => Err(error::unterminated_free_form_element()),
+        None => Ok(()),
+    }
+}
+
+fn length_check(tokens: &[Token], min: usize, max: usize) -> Result<(), StatementError> {
+    if !(min..=max).contains(&tokens.len()) {
+        return Err(error::invalid_argument_length(
+            format!("{min}..{max}"),
+            tokens.len(),
+        ))
+    }
+    Ok(())
+}
+
+/// Bounds of the part of the global parameters an element or trimming curve
+/// spans, which may be reversed but not infinite or NaN.
+fn range_parse(start: &Token, end: &Token) -> Result<[f32; 2], StatementError> {
+    let mut range = [0.0; 2];
+    for (bound, token) in range.iter_mut().zip([start, end]) {
+        *bound = token.parse_f32()?;
+        if !bound.is_finite() {
+            return Err(token.locate(error::invalid_parameters()));
+        }
+    }
+    Ok(range)
+}
+
+/// `u` or `v`, the latter being lexed as the vertex keyword.
+fn direction_parse(token: &Token) -> Result<usize, StatementError> {
+    match token {
+        Token::Value { value: "u", .. } => Ok(U),
+        Token::Vertex => Ok(V),
+        _ => Err(token.locate(error::invalid_token(token))),
+    }
+}
+
+fn unsigned_parse(token: &Token) -> Result<usize, StatementError> {
+    token.extract_value()?
+        .parse()
+        .map_err(|e| token.locate(error::custom(e)))
+}
+
+fn index_parse(token: &Token) -> Result<isize, StatementError> {
+    parse_index(token.extract_value()?)
+        .map_err(|e| token.locate(e))?
+        .ok_or_else(|| token.locate(error::invalid_statement()))
+}
+
+fn numbers_parse(tokens: &[Token]) -> Result<Vec<f32>, StatementError> {
+    tokens.iter().map(Token::parse_f32).collect()
+}
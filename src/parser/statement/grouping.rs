@@ -0,0 +1,49 @@
+use crate::error::{self, StatementError};
+use crate::object::{Object, SMOOTHING_GROUP_OFF};
+use crate::parser::Token;
+use crate::parser::state::State;
+
+const S_STATEMENT_LENGTH: usize = 1;
+const O_MIN_STATEMENT_LENGTH: usize = 1;
+const DEFAULT_GROUP: &str = "default";
+
+pub fn smoothing_group_parse(tokens: &[Token], state: &mut State) -> Result<(), StatementError> {
+    if tokens.len() != S_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!("{S_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let value = tokens[0].extract_value()?;
+    state.smoothing_group = match value {
+        "off" => SMOOTHING_GROUP_OFF,
+        _ => value.parse()
+            .map_err(|e| tokens[0].locate(error::custom(e)))?,
+    };
+    Ok(())
+}
+
+/// Object names may contain spaces, which are kept as single separators, and
+/// words spelling an OBJ keyword, as `usemtl` names do.
+pub fn object_name_parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
+    if tokens.len() < O_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {O_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let name = tokens.iter().map(Token::to_string).collect::<Vec<_>>().join(" ");
+    object.begin_object(name);
+    Ok(())
+}
+
+/// Each argument names a group the following faces belong to. Without any,
+/// the faces go back to the default group.
+pub fn group_name_parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
+    let mut names: Vec<_> = tokens.iter().map(Token::to_string).collect();
+    if names.is_empty() {
+        names.push(DEFAULT_GROUP.to_string());
+    }
+    object.begin_groups(names);
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::error::{self, StatementError};
+use crate::object::Object;
+use crate::parser::Token;
+use crate::parser::state::State;
+
+const MTLLIB_MIN_STATEMENT_LENGTH: usize = 1;
+const USEMTL_MIN_STATEMENT_LENGTH: usize = 1;
+
+pub fn material_library_parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
+    if tokens.len() < MTLLIB_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {MTLLIB_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    for token in tokens {
+        object.material_libraries.push(token.extract_value()?.to_string());
+    }
+    Ok(())
+}
+
+/// Names spanning several words are joined by single spaces, as `newmtl`
+/// does, words spelling an OBJ keyword included.
+pub fn material_name_parse(tokens: &[Token], object: &mut Object, state: &mut State) -> Result<(), StatementError> {
+    if tokens.len() < USEMTL_MIN_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!(">= {USEMTL_MIN_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    let name = tokens.iter().map(Token::to_string).collect::<Vec<_>>().join(" ");
+    state.material = Some(object.material_index(&name));
+    Ok(())
+}
@@ -1,35 +1,52 @@
-use std::io;
-use crate::error;
-use crate::object::{Object, Vertex, VertexNormal, VertexTexture};
+use crate::error::{self, StatementError};
+use crate::object::{Object, Vertex, VertexNormal, VertexParameter, VertexTexture};
 use crate::parser::Token;
 use crate::parser::token::ParseOptional;
 
-const V_MIN_STATEMENT_LENGTH: usize = 3;
-const V_MAX_STATEMENT_LENGTH: usize = 4;
+const V_STATEMENT_LENGTH: usize = 3;
+const V_WEIGHT_STATEMENT_LENGTH: usize = 4;
+const V_COLOR_STATEMENT_LENGTH: usize = 6;
+const V_WEIGHT_COLOR_STATEMENT_LENGTH: usize = 7;
 const VN_STATEMENT_LENGTH: usize = 3;
 const VT_MIN_STATEMENT_LENGTH: usize = 1;
 const VT_MAX_STATEMENT_LENGTH: usize = 3;
+const VP_MIN_STATEMENT_LENGTH: usize = 1;
+const VP_MAX_STATEMENT_LENGTH: usize = 3;
 
 impl Vertex {
-    pub fn parse(tokens: Vec<Token>, object: &mut Object) -> Result<(), io::Error> {
-        let range = V_MIN_STATEMENT_LENGTH..V_MAX_STATEMENT_LENGTH;
-        if !range.contains(&tokens.len()) {
-            return Err(error::invalid_argument_length(
-                format!("{V_MIN_STATEMENT_LENGTH}..{V_MAX_STATEMENT_LENGTH}"),
+    /// `x y z [w] [r g b]`, the colour being the vertex colours extension.
+    pub fn parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
+        let (mut w, color) = match tokens.len() {
+            V_STATEMENT_LENGTH => (None, None),
+            V_WEIGHT_STATEMENT_LENGTH => (tokens.get(3), None),
+            V_COLOR_STATEMENT_LENGTH => (None, Some(&tokens[3..])),
+            V_WEIGHT_COLOR_STATEMENT_LENGTH => (tokens.get(3), Some(&tokens[4..])),
+            _ => return Err(error::invalid_argument_length(
+                format!(
+                    "{V_STATEMENT_LENGTH}, {V_WEIGHT_STATEMENT_LENGTH}, \
+                    {V_COLOR_STATEMENT_LENGTH} or {V_WEIGHT_COLOR_STATEMENT_LENGTH}"
+                ),
                 tokens.len(),
-            ))
-        }
+            )),
+        };
         let x = tokens[0].parse_f32()?;
         let y = tokens[1].parse_f32()?;
         let z = tokens[2].parse_f32()?;
-        let w = tokens.get(3).parse_optional_f32()?;
-        object.add_vertex(Vertex::new(x, y, z, w));
+        let mut vertex = Vertex::new(x, y, z, w.parse_optional_f32()?);
+        if let Some(color) = color {
+            vertex.color = Some([
+                color[0].parse_f32()?,
+                color[1].parse_f32()?,
+                color[2].parse_f32()?,
+            ]);
+        }
+        object.add_vertex(vertex);
         Ok(())
     }
 }
 
 impl VertexNormal {
-    pub fn parse(tokens: Vec<Token>, object: &mut Object) -> Result<(), io::Error> {
+    pub fn parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
         if tokens.len() != VN_STATEMENT_LENGTH {
             return Err(error::invalid_argument_length(
                 format!("{VN_STATEMENT_LENGTH}"),
@@ -45,7 +62,7 @@ impl VertexNormal {
 }
 
 impl VertexTexture {
-    pub fn parse(tokens: Vec<Token>, object: &mut Object) -> Result<(), io::Error> {
+    pub fn parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
         let range = VT_MIN_STATEMENT_LENGTH..=VT_MAX_STATEMENT_LENGTH;
         if !range.contains(&tokens.len()) {
             return Err(error::invalid_argument_length(
@@ -60,3 +77,20 @@ impl VertexTexture {
         Ok(())
     }
 }
+
+impl VertexParameter {
+    pub fn parse(tokens: &[Token], object: &mut Object) -> Result<(), StatementError> {
+        let range = VP_MIN_STATEMENT_LENGTH..=VP_MAX_STATEMENT_LENGTH;
+        if !range.contains(&tokens.len()) {
+            return Err(error::invalid_argument_length(
+                format!("{VP_MIN_STATEMENT_LENGTH}..{VP_MAX_STATEMENT_LENGTH}"),
+                tokens.len(),
+            ))
+        }
+        let u = tokens[0].parse_f32()?;
+        let v = tokens.get(1).parse_optional_f32()?;
+        let w = tokens.get(2).parse_optional_f32()?;
+        object.add_vertex_parameter(VertexParameter::new(u, v, w));
+        Ok(())
+    }
+}
@@ -1,55 +1,180 @@
-use std::io;
-use crate::error;
+use std::fmt;
+use crate::error::{self, StatementError};
+use crate::parser::float;
 
-#[derive(Debug, Clone)]
-pub enum Token {
+const CONTINUATION: u8 = b'\\';
+
+#[derive(Debug, Clone, Copy)]
+pub enum Token<'a> {
     Vertex,
     VertexTexture,
     VertexNormal,
+    VertexParameter,
     Face,
+    Line,
+    Point,
     SmoothingGroup,
     ObjectName,
+    GroupName,
     MaterailName,
     MaterialLibrary,
+    CurveType,
+    Degree,
+    BasisMatrix,
+    Step,
+    Curve,
+    Curve2,
+    Surface,
+    Parameter,
+    Trim,
+    Hole,
+    End,
     Comment,
-    Value(String),
+    Value {
+        value: &'a str,
+        column: usize,
+    },
 }
 
-impl Token {
-    fn from(s: &str) -> Self {
+impl<'a> Token<'a> {
+    fn from(s: &'a str, column: usize) -> Self {
         match s {
             "v" => Self::Vertex,
             "vt" => Self::VertexTexture,
             "vn" => Self::VertexNormal,
+            "vp" => Self::VertexParameter,
             "f" => Self::Face,
+            "l" => Self::Line,
+            "p" => Self::Point,
             "s" => Self::SmoothingGroup,
             "o" => Self::ObjectName,
+            "g" => Self::GroupName,
             "usemtl" => Self::MaterailName,
             "mtllib" => Self::MaterialLibrary,
+            "cstype" => Self::CurveType,
+            "deg" => Self::Degree,
+            "bmat" => Self::BasisMatrix,
+            "step" => Self::Step,
+            "curv" => Self::Curve,
+            "curv2" => Self::Curve2,
+            "surf" => Self::Surface,
+            "parm" => Self::Parameter,
+            "trim" => Self::Trim,
+            "hole" => Self::Hole,
+            "end" => Self::End,
+            _ => Self::word(s, column),
+        }
+    }
+
+    /// Only `#` has a meaning of its own among plain words.
+    fn word(s: &'a str, column: usize) -> Self {
+        match s {
             "#" => Self::Comment,
-            _ => Self::Value(s.to_string()),
+            _ => Self::Value {
+                value: s,
+                column,
+            },
+        }
+    }
+
+    /// Splits `line` on ASCII whitespace into `tokens`, recording the 1-based
+    /// column of each value. A backslash ending a physical line is a separator
+    /// as well, so that continued lines lex as one. `tokens` is cleared first,
+    /// letting callers reuse its allocation from one line to the next.
+    pub fn lex(line: &'a str, tokens: &mut Vec<Token<'a>>) {
+        Self::lex_with(line, tokens, Self::from);
+    }
+
+    /// Lexes `line` like [`Token::lex`], but as plain words: none of them
+    /// becomes an OBJ keyword, so that formats with keywords of their own,
+    /// such as MTL, get their values back whatever they spell.
+    pub fn lex_words(line: &'a str, tokens: &mut Vec<Token<'a>>) {
+        Self::lex_with(line, tokens, Self::word);
+    }
+
+    fn lex_with(line: &'a str, tokens: &mut Vec<Token<'a>>, from: fn(&'a str, usize) -> Self) {
+        tokens.clear();
+        let bytes = line.as_bytes();
+        let mut start = None;
+        let mut column = 0;
+        for (index, &byte) in bytes.iter().enumerate() {
+            // Only the first byte of a UTF-8 sequence starts a new column.
+            if byte & 0xC0 != 0x80 {
+                column += 1;
+            }
+            let separator = byte.is_ascii_whitespace()
+                || (byte == CONTINUATION && matches!(bytes.get(index + 1), None | Some(b'\r' | b'\n')));
+            match (start, separator) {
+                (None, false) => start = Some((index, column)),
+                (Some((begin, begin_column)), true) => {
+                    tokens.push(from(&line[begin..index], begin_column));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        if let Some((begin, begin_column)) = start {
+            tokens.push(from(&line[begin..], begin_column));
         }
     }
 
-    pub fn lex(line: &str) -> Vec<Token> {
-        line.split_whitespace()
-            .map(Token::from)
-            .collect()
+    /// Points `error` at this token when its column in the source line is known.
+    pub fn locate(&self, error: StatementError) -> StatementError {
+        match self {
+            Token::Value { column, .. } => error.at(*column),
+            _ => error,
+        }
     }
 
-    pub fn extract_value(&self) -> Result<&String, io::Error> {
+    pub fn extract_value(&self) -> Result<&'a str, StatementError> {
         match self {
-            Token::Value(str) => Ok(str),
+            Token::Value { value, .. } => Ok(value),
             _ => Err(error::non_value_token()),
         }
     }
 
-    pub fn parse_f32(&self) -> Result<f32, io::Error> {
-        let res = self.extract_value()?
-            .parse::<f32>()
-            .map_err(error::custom)?;
+    /// Parses the value as a float, the standard library handling the
+    /// notations that [`float::parse_f32`] cannot compute exactly.
+    pub fn parse_f32(&self) -> Result<f32, StatementError> {
+        let value = self.extract_value()?;
+        match float::parse_f32(value) {
+            Some(res) => Ok(res),
+            None => value.parse::<f32>()
+                .map_err(|e| self.locate(error::custom(e))),
+        }
+    }
+}
 
-        Ok(res)
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Token::Vertex => "v",
+            Token::VertexTexture => "vt",
+            Token::VertexNormal => "vn",
+            Token::VertexParameter => "vp",
+            Token::Face => "f",
+            Token::Line => "l",
+            Token::Point => "p",
+            Token::SmoothingGroup => "s",
+            Token::ObjectName => "o",
+            Token::GroupName => "g",
+            Token::MaterailName => "usemtl",
+            Token::MaterialLibrary => "mtllib",
+            Token::CurveType => "cstype",
+            Token::Degree => "deg",
+            Token::BasisMatrix => "bmat",
+            Token::Step => "step",
+            Token::Curve => "curv",
+            Token::Curve2 => "curv2",
+            Token::Surface => "surf",
+            Token::Parameter => "parm",
+            Token::Trim => "trim",
+            Token::Hole => "hole",
+            Token::End => "end",
+            Token::Comment => "#",
+            Token::Value { value, .. } => *value,
+        };
+        write!(f, "{s}")
     }
 }
 
@@ -57,7 +182,7 @@ pub trait RemoveComment {
     fn remove_comment(&mut self);
 }
 
-impl RemoveComment for Vec<Token> {
+impl RemoveComment for Vec<Token<'_>> {
     fn remove_comment(&mut self) {
         if let Some(comment_pos) = self
             .iter()
@@ -69,11 +194,11 @@ impl RemoveComment for Vec<Token> {
 }
 
 pub trait ParseOptional {
-    fn parse_optional_f32(&mut self) -> Result<Option<f32>, io::Error>;
+    fn parse_optional_f32(&mut self) -> Result<Option<f32>, StatementError>;
 }
 
-impl ParseOptional for Option<&Token> {
-    fn parse_optional_f32(&mut self) -> Result<Option<f32>, io::Error> {
+impl ParseOptional for Option<&Token<'_>> {
+    fn parse_optional_f32(&mut self) -> Result<Option<f32>, StatementError> {
         let res = match self {
             Some(token) => Some(token.parse_f32()?),
             None => None,
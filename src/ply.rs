@@ -0,0 +1,469 @@
+mod data;
+mod header;
+
+use std::fs;
+use std::io::{self, Write};
+use ahash::{HashMap, HashMapExt};
+use crate::error::{self, ParseError};
+use crate::object::{Corner, Face, Object, Point, Vertex, VertexNormal, VertexTexture};
+use data::{AsciiValues, BinaryValues, ValueWriter, Values};
+use header::{Element, Header, Property, PropertyKind};
+
+const VERTEX_ELEMENT: &str = "vertex";
+const FACE_ELEMENT: &str = "face";
+const FACE_INDICES: [&str; 2] = ["vertex_indices", "vertex_index"];
+const FACE_MIN_CORNERS: usize = 3;
+const POSITION: [&str; 3] = ["x", "y", "z"];
+const NORMAL: [&str; 3] = ["nx", "ny", "nz"];
+const COLOR: [&str; 3] = ["red", "green", "blue"];
+const TEXTURE: [[&str; 2]; 4] = [["u", "v"], ["s", "t"], ["texture_u", "texture_v"], ["texture_s", "texture_t"]];
+
+/// Encoding of the data following the header of a PLY file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlyFormat {
+    Ascii,
+    /// The byte order of most machines.
+    #[default]
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Type of a PLY property value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarType {
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+}
+
+impl ScalarType {
+    /// Parses both the original names, such as `uchar`, and the sized ones,
+    /// such as `uint8`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::Int8),
+            "uchar" | "uint8" => Some(Self::Uint8),
+            "short" | "int16" => Some(Self::Int16),
+            "ushort" | "uint16" => Some(Self::Uint16),
+            "int" | "int32" => Some(Self::Int32),
+            "uint" | "uint32" => Some(Self::Uint32),
+            "float" | "float32" => Some(Self::Float32),
+            "double" | "float64" => Some(Self::Float64),
+            _ => None,
+        }
+    }
+
+    /// Original name of the type, the one most readers understand.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Int8 => "char",
+            Self::Uint8 => "uchar",
+            Self::Int16 => "short",
+            Self::Uint16 => "ushort",
+            Self::Int32 => "int",
+            Self::Uint32 => "uint",
+            Self::Float32 => "float",
+            Self::Float64 => "double",
+        }
+    }
+
+    /// Size of a binary value, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Int8 | Self::Uint8 => 1,
+            Self::Int16 | Self::Uint16 => 2,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    pub fn is_integer(self) -> bool {
+        !matches!(self, Self::Float32 | Self::Float64)
+    }
+
+    /// Value standing for full intensity in a colour channel of this type.
+    fn intensity(self) -> f64 {
+        match self {
+            Self::Int8 => i8::MAX as f64,
+            Self::Uint8 => u8::MAX as f64,
+            Self::Int16 => i16::MAX as f64,
+            Self::Uint16 => u16::MAX as f64,
+            Self::Int32 => i32::MAX as f64,
+            Self::Uint32 => u32::MAX as f64,
+            Self::Float32 | Self::Float64 => 1.0,
+        }
+    }
+}
+
+/// Per-vertex property with no counterpart in [`Object`], such as the
+/// confidence or intensity of a scanned point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexAttribute {
+    pub name: String,
+    /// Type the values are stored as in PLY files.
+    pub scalar: ScalarType,
+    /// One value per vertex.
+    pub values: Vec<f64>,
+}
+
+/// Mesh read from a PLY file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ply {
+    pub object: Object,
+    /// Vertex properties other than the position, normal, colour and texture
+    /// coordinates, in the order of the header.
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl Ply {
+    pub fn attribute(&self, name: &str) -> Option<&VertexAttribute> {
+        self.attributes.iter().find(|attribute| attribute.name == name)
+    }
+}
+
+/// Parses the PLY file at `path`, see [`parse_bytes`].
+pub fn parse(path: &str) -> Result<Ply, ParseError> {
+    let bytes = fs::read(path)
+        .map_err(|e| ParseError::io(path, e))?;
+    parse_bytes(&bytes, path)
+}
+
+/// Parses an ASCII or binary PLY file, errors being reported under `name`.
+///
+/// The `x`, `y` and `z` properties of the `vertex` element give the
+/// vertices. Normals, colours and texture coordinates are recognised by their
+/// usual names, normals and texture coordinates being stored parallel to the
+/// vertices, and any other vertex property becomes a [`VertexAttribute`].
+/// Faces are read from the `vertex_indices` list of the `face` element, and
+/// other elements are skipped. A file without faces gives a single point
+/// holding every vertex, so that point clouds can be displayed.
+pub fn parse_bytes(bytes: &[u8], name: &str) -> Result<Ply, ParseError> {
+    let (header, offset, lines) = header::parse(bytes, name)?;
+    let data = &bytes[offset..];
+    let mut ply = match header.format {
+        PlyFormat::Ascii => {
+            let source = String::from_utf8_lossy(data);
+            read(&header, &mut AsciiValues::new(&source, name, lines), name)?
+        }
+        PlyFormat::BinaryLittleEndian => read(&header, &mut BinaryValues::new(data, name, false), name)?,
+        PlyFormat::BinaryBigEndian => read(&header, &mut BinaryValues::new(data, name, true), name)?,
+    };
+    let object = &mut ply.object;
+    if object.faces.is_empty() && !object.vertices.is_empty() {
+        object.add_point(Point {
+            vertices: (0..object.vertices.len()).collect(),
+            material: None,
+        });
+    }
+    Ok(ply)
+}
+
+/// Columns of the vertex element holding each piece of a vertex.
+struct VertexLayout {
+    position: [usize; 3],
+    normal: Option<[usize; 3]>,
+    color: Option<[usize; 3]>,
+    texture: Option<[usize; 2]>,
+    attributes: Vec<usize>,
+}
+
+impl VertexLayout {
+    fn new(element: &Element, name: &str) -> Result<Self, ParseError> {
+        let column = |property: &str| element.properties.iter().position(|p| {
+            p.name == property && matches!(p.kind, PropertyKind::Scalar(_))
+        });
+        let columns = |properties: &[&str]| properties.iter().map(|&p| column(p)).collect::<Option<Vec<_>>>();
+        let position = columns(&POSITION)
+            .ok_or_else(|| ParseError::invalid(name, "Vertex element without x, y and z properties"))?;
+        let normal = columns(&NORMAL);
+        let color = columns(&COLOR);
+        let texture = TEXTURE.iter().find_map(|names| columns(names));
+        let used: Vec<usize> = position.iter()
+            .chain(normal.iter().flatten())
+            .chain(color.iter().flatten())
+            .chain(texture.iter().flatten())
+            .copied()
+            .collect();
+        let attributes = element.properties.iter().enumerate()
+            .filter(|(i, p)| matches!(p.kind, PropertyKind::Scalar(_)) && !used.contains(i))
+            .map(|(i, _)| i)
+            .collect();
+        Ok(Self {
+            position: position.try_into().unwrap(),
+            normal: normal.map(|columns| columns.try_into().unwrap()),
+            color: color.map(|columns| columns.try_into().unwrap()),
+            texture: texture.map(|columns| columns.try_into().unwrap()),
+            attributes,
+        })
+    }
+}
+
+fn read(header: &Header, values: &mut impl Values, name: &str) -> Result<Ply, ParseError> {
+    let mut ply = Ply {
+        object: Object::new(),
+        attributes: vec![],
+    };
+    let vertex_count = header.elements.iter()
+        .find(|element| element.name == VERTEX_ELEMENT)
+        .map_or(0, |element| element.count);
+    let mut row = vec![];
+    let mut list = vec![];
+    for element in &header.elements {
+        let layout = match element.name.as_str() {
+            VERTEX_ELEMENT => Some(VertexLayout::new(element, name)?),
+            _ => None,
+        };
+        if let Some(layout) = &layout {
+            ply.attributes = layout.attributes.iter()
+                .map(|&i| {
+                    let PropertyKind::Scalar(scalar) = element.properties[i].kind else { unreachable!() };
+                    VertexAttribute {
+                        name: element.properties[i].name.clone(),
+                        scalar,
+                        values: vec![],
+                    }
+                })
+                .collect();
+        }
+        let indices = FACE_INDICES.iter()
+            .find_map(|&indices| element.properties.iter().position(|p| {
+                p.name == indices && matches!(p.kind, PropertyKind::List(..))
+            }))
+            .filter(|_| element.name == FACE_ELEMENT);
+        for _ in 0..element.count {
+            values.begin()?;
+            row.clear();
+            for (i, property) in element.properties.iter().enumerate() {
+                match property.kind {
+                    PropertyKind::Scalar(scalar) => row.push(values.value(scalar)?),
+                    PropertyKind::List(count, item) => {
+                        row.push(0.0);
+                        let count = values.value(count)?;
+                        if indices == Some(i) {
+                            list.clear();
+                        }
+                        for _ in 0..count as usize {
+                            let value = values.value(item)?;
+                            if indices == Some(i) {
+                                list.push(value);
+                            }
+                        }
+                    }
+                }
+            }
+            values.end()?;
+            if let Some(layout) = &layout {
+                vertex_add(&mut ply, layout, element, &row);
+            }
+            if indices.is_some() {
+                let face = face_parse(&ply.object, &list, vertex_count)
+                    .map_err(|e| values.error(e))?;
+                ply.object.add_face(face);
+            }
+        }
+    }
+    Ok(ply)
+}
+
+fn vertex_add(ply: &mut Ply, layout: &VertexLayout, element: &Element, row: &[f64]) {
+    let object = &mut ply.object;
+    let [x, y, z] = layout.position.map(|i| row[i] as f32);
+    let mut vertex = Vertex::new(x, y, z, None);
+    vertex.color = layout.color.map(|columns| columns.map(|i| {
+        let PropertyKind::Scalar(scalar) = element.properties[i].kind else { unreachable!() };
+        (row[i] / scalar.intensity()) as f32
+    }));
+    object.add_vertex(vertex);
+    if let Some(columns) = layout.normal {
+        let [i, j, k] = columns.map(|i| row[i] as f32);
+        object.add_vertex_normal(VertexNormal::new(i, j, k));
+    }
+    if let Some([u, v]) = layout.texture {
+        object.add_vertex_texture(VertexTexture::new(row[u] as f32, Some(row[v] as f32), None));
+    }
+    for (attribute, &i) in ply.attributes.iter_mut().zip(&layout.attributes) {
+        attribute.values.push(row[i]);
+    }
+}
+
+/// Face whose corners use the normal and texture coordinates parallel to
+/// their vertex, when the file has some.
+fn face_parse(object: &Object, indices: &[f64], vertex_count: usize) -> Result<Face, error::StatementError> {
+    if indices.len() < FACE_MIN_CORNERS {
+        return Err(error::invalid_face(indices.len()));
+    }
+    let mut face = Face::new();
+    for &index in indices {
+        if !index.is_finite() || index.fract() != 0.0 {
+            return Err(error::non_integer_index(index));
+        }
+        if index < 0.0 || index >= vertex_count as f64 {
+            return Err(error::index_out_of_bound(index as isize, vertex_count));
+        }
+        let vertex = index as usize;
+        face.corners.push(Corner {
+            vertex,
+            texture: (object.vertices_texture.len() == vertex_count).then_some(vertex),
+            normal: (object.vertices_normal.len() == vertex_count).then_some(vertex),
+        });
+    }
+    Ok(face)
+}
+
+/// Vertex of the written file, pairing a vertex of the object with the
+/// texture coordinates and normal its corners use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PlyVertex {
+    vertex: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// Writes the vertices and faces of `object` as a PLY file, along with
+/// `attributes`, which must hold one value per vertex.
+///
+/// PLY vertices carry a single normal and texture coordinate, so a vertex
+/// whose corners use several of them is written once for each. Vertices no
+/// face uses take the normal and texture coordinates parallel to them, if
+/// any. Lines have no PLY counterpart and are left out.
+pub fn write(object: &Object, attributes: &[VertexAttribute], mut writer: impl Write, format: PlyFormat) -> io::Result<()> {
+    if let Some(attribute) = attributes.iter().find(|a| a.values.len() != object.vertices.len()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+            "Attribute '{}' has {} values for {} vertices",
+            attribute.name,
+            attribute.values.len(),
+            object.vertices.len(),
+        )));
+    }
+    if let Some(face) = object.faces.iter().find(|face| face.corners.len() > u8::MAX as usize) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+            "Face with {} corners, PLY faces hold at most {}",
+            face.corners.len(),
+            u8::MAX,
+        )));
+    }
+    let (vertices, faces) = split_vertices(object);
+    let has_normal = vertices.iter().any(|vertex| vertex.normal.is_some());
+    let has_texture = vertices.iter().any(|vertex| vertex.texture.is_some());
+    let has_color = object.vertices.iter().any(|vertex| vertex.color.is_some());
+
+    let scalar = |name: &str, scalar| Property {
+        name: name.to_string(),
+        kind: PropertyKind::Scalar(scalar),
+    };
+    let mut properties: Vec<_> = POSITION.iter().map(|name| scalar(name, ScalarType::Float32)).collect();
+    if has_normal {
+        properties.extend(NORMAL.iter().map(|name| scalar(name, ScalarType::Float32)));
+    }
+    if has_color {
+        properties.extend(COLOR.iter().map(|name| scalar(name, ScalarType::Uint8)));
+    }
+    if has_texture {
+        properties.extend(TEXTURE[0].iter().map(|name| scalar(name, ScalarType::Float32)));
+    }
+    properties.extend(attributes.iter().map(|attribute| scalar(&attribute.name, attribute.scalar)));
+    let mut elements = vec![Element {
+        name: VERTEX_ELEMENT.to_string(),
+        count: vertices.len(),
+        properties,
+    }];
+    if !faces.is_empty() {
+        elements.push(Element {
+            name: FACE_ELEMENT.to_string(),
+            count: faces.len(),
+            properties: vec![Property {
+                name: FACE_INDICES[0].to_string(),
+                kind: PropertyKind::List(ScalarType::Uint8, ScalarType::Int32),
+            }],
+        });
+    }
+    Header { format, elements }.write(&mut writer)?;
+
+    let mut out = ValueWriter::new(writer, format);
+    for ply_vertex in &vertices {
+        let vertex = &object.vertices[ply_vertex.vertex];
+        for component in vertex.position() {
+            out.value(ScalarType::Float32, component as f64)?;
+        }
+        if has_normal {
+            let normal = ply_vertex.normal.map_or([0.0; 3], |normal| {
+                let normal = object.vertices_normal[normal];
+                [normal.i, normal.j, normal.k]
+            });
+            for component in normal {
+                out.value(ScalarType::Float32, component as f64)?;
+            }
+        }
+        if has_color {
+            for channel in vertex.color.unwrap_or([1.0; 3]) {
+                let channel = (channel.clamp(0.0, 1.0) as f64 * ScalarType::Uint8.intensity()).round();
+                out.value(ScalarType::Uint8, channel)?;
+            }
+        }
+        if has_texture {
+            let [u, v] = ply_vertex.texture.map_or([0.0; 2], |texture| {
+                let texture = object.vertices_texture[texture];
+                [texture.u, texture.v.unwrap_or(0.0)]
+            });
+            out.value(ScalarType::Float32, u as f64)?;
+            out.value(ScalarType::Float32, v as f64)?;
+        }
+        for attribute in attributes {
+            out.value(attribute.scalar, attribute.values[ply_vertex.vertex])?;
+        }
+        out.end()?;
+    }
+    for face in &faces {
+        out.value(ScalarType::Uint8, face.len() as f64)?;
+        for &index in face {
+            out.value(ScalarType::Int32, index as f64)?;
+        }
+        out.end()?;
+    }
+    Ok(())
+}
+
+/// Gives every vertex of the object a PLY vertex, taking the texture
+/// coordinates and normal of the first corner using it, and adds one for each
+/// other combination the corners use. Returns the PLY vertices and the faces
+/// as indices into them.
+fn split_vertices(object: &Object) -> (Vec<PlyVertex>, Vec<Vec<usize>>) {
+    let parallel = |len: usize, vertex: usize| (len == object.vertices.len()).then_some(vertex);
+    let mut vertices: Vec<_> = (0..object.vertices.len())
+        .map(|vertex| PlyVertex {
+            vertex,
+            texture: parallel(object.vertices_texture.len(), vertex),
+            normal: parallel(object.vertices_normal.len(), vertex),
+        })
+        .collect();
+    let mut claimed = vec![false; vertices.len()];
+    let mut splits = HashMap::new();
+    let faces = object.faces.iter()
+        .map(|face| face.corners.iter()
+            .map(|corner| {
+                let ply_vertex = PlyVertex {
+                    vertex: corner.vertex,
+                    texture: corner.texture,
+                    normal: corner.normal,
+                };
+                if !claimed[corner.vertex] {
+                    claimed[corner.vertex] = true;
+                    vertices[corner.vertex] = ply_vertex;
+                }
+                if vertices[corner.vertex] == ply_vertex {
+                    return corner.vertex;
+                }
+                *splits.entry(ply_vertex).or_insert_with(|| {
+                    vertices.push(ply_vertex);
+                    vertices.len() - 1
+                })
+            })
+            .collect())
+        .collect();
+    (vertices, faces)
+}
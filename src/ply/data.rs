@@ -0,0 +1,204 @@
+use std::io::{self, Write};
+use crate::error::{self, ParseError, StatementError};
+use crate::parser::Token;
+use crate::parser::reader::{Line, Lines};
+use crate::ply::{PlyFormat, ScalarType};
+
+/// Values of the elements, read in the order the header declares them.
+pub trait Values {
+    /// Starts the next element.
+    fn begin(&mut self) -> Result<(), ParseError>;
+    fn value(&mut self, scalar: ScalarType) -> Result<f64, ParseError>;
+    /// Ends the element, checking it has no other values.
+    fn end(&mut self) -> Result<(), ParseError>;
+    /// Locates an error in the element being read.
+    fn error(&self, e: StatementError) -> ParseError;
+}
+
+/// Elements written one per line, their values separated by spaces.
+pub struct AsciiValues<'a> {
+    name: &'a str,
+    lines: Lines<'a>,
+    /// Lines of the header, so that numbers refer to the whole file.
+    offset: usize,
+    line: Option<Line<'a>>,
+    tokens: Vec<Token<'a>>,
+    next: usize,
+}
+
+pub struct BinaryValues<'a> {
+    name: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> AsciiValues<'a> {
+    pub fn new(source: &'a str, name: &'a str, offset: usize) -> Self {
+        Self {
+            name,
+            lines: Lines::new(source),
+            offset,
+            line: None,
+            tokens: vec![],
+            next: 0,
+        }
+    }
+}
+
+impl Values for AsciiValues<'_> {
+    fn begin(&mut self) -> Result<(), ParseError> {
+        loop {
+            let Some(line) = self.lines.next() else {
+                return Err(ParseError::invalid(self.name, "Unexpected end of data"));
+            };
+            Token::lex(line.text, &mut self.tokens);
+            self.line = Some(line);
+            self.next = 0;
+            if !self.tokens.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn value(&mut self, scalar: ScalarType) -> Result<f64, ParseError> {
+        let Some(token) = self.tokens.get(self.next) else {
+            return Err(self.error(error::missing_value()));
+        };
+        self.next += 1;
+        let value = token.extract_value()
+            .and_then(|value| value.parse::<f64>().map_err(|e| token.locate(error::custom(e))))
+            .and_then(|value| match scalar.is_integer() && value.fract() != 0.0 {
+                true => Err(token.locate(error::invalid_token(token))),
+                false => Ok(value),
+            });
+        value.map_err(|e| self.error(e))
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        match self.tokens.get(self.next) {
+            Some(token) => Err(self.error(token.locate(error::unexpected_token(token)))),
+            None => Ok(()),
+        }
+    }
+
+    fn error(&self, e: StatementError) -> ParseError {
+        match &self.line {
+            Some(line) => ParseError::syntax(self.name, self.offset + line.number, &line.source(), e),
+            None => ParseError::invalid(self.name, e),
+        }
+    }
+}
+
+impl<'a> BinaryValues<'a> {
+    pub fn new(bytes: &'a [u8], name: &'a str, big_endian: bool) -> Self {
+        Self {
+            name,
+            bytes,
+            position: 0,
+            big_endian,
+        }
+    }
+}
+
+/// Reads a `$type` in the byte order of the values.
+macro_rules! decode {
+    ($type:ty, $bytes:expr, $big_endian:expr) => {{
+        let bytes = $bytes.try_into().unwrap();
+        (if $big_endian { <$type>::from_be_bytes(bytes) } else { <$type>::from_le_bytes(bytes) }) as f64
+    }};
+}
+
+impl Values for BinaryValues<'_> {
+    fn begin(&mut self) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn value(&mut self, scalar: ScalarType) -> Result<f64, ParseError> {
+        let bytes = self.bytes.get(self.position..self.position + scalar.size())
+            .ok_or_else(|| ParseError::invalid(self.name, format!("Unexpected end of data at byte {}", self.position)))?;
+        self.position += scalar.size();
+        let big_endian = self.big_endian;
+        Ok(match scalar {
+            ScalarType::Int8 => decode!(i8, bytes, big_endian),
+            ScalarType::Uint8 => decode!(u8, bytes, big_endian),
+            ScalarType::Int16 => decode!(i16, bytes, big_endian),
+            ScalarType::Uint16 => decode!(u16, bytes, big_endian),
+            ScalarType::Int32 => decode!(i32, bytes, big_endian),
+            ScalarType::Uint32 => decode!(u32, bytes, big_endian),
+            ScalarType::Float32 => decode!(f32, bytes, big_endian),
+            ScalarType::Float64 => decode!(f64, bytes, big_endian),
+        })
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn error(&self, e: StatementError) -> ParseError {
+        ParseError::invalid(self.name, format!("{e} before byte {}", self.position))
+    }
+}
+
+/// Writes the values of the elements in the given format.
+pub struct ValueWriter<W> {
+    out: W,
+    format: PlyFormat,
+    /// Whether the current ASCII line already holds a value.
+    separate: bool,
+}
+
+/// Writes a `$type` in the byte order of the format.
+macro_rules! encode {
+    ($out:expr, $type:ty, $value:expr, $big_endian:expr) => {{
+        let value = $value as $type;
+        $out.write_all(&if $big_endian { value.to_be_bytes() } else { value.to_le_bytes() })
+    }};
+}
+
+impl<W: Write> ValueWriter<W> {
+    pub fn new(out: W, format: PlyFormat) -> Self {
+        Self {
+            out,
+            format,
+            separate: false,
+        }
+    }
+
+    pub fn value(&mut self, scalar: ScalarType, value: f64) -> io::Result<()> {
+        let big_endian = match self.format {
+            PlyFormat::Ascii => {
+                if self.separate {
+                    write!(self.out, " ")?;
+                }
+                self.separate = true;
+                return match scalar {
+                    ScalarType::Float32 => write!(self.out, "{}", value as f32),
+                    ScalarType::Float64 => write!(self.out, "{value}"),
+                    _ => write!(self.out, "{}", value as i64),
+                };
+            }
+            PlyFormat::BinaryLittleEndian => false,
+            PlyFormat::BinaryBigEndian => true,
+        };
+        match scalar {
+            ScalarType::Int8 => encode!(self.out, i8, value, big_endian),
+            ScalarType::Uint8 => encode!(self.out, u8, value, big_endian),
+            ScalarType::Int16 => encode!(self.out, i16, value, big_endian),
+            ScalarType::Uint16 => encode!(self.out, u16, value, big_endian),
+            ScalarType::Int32 => encode!(self.out, i32, value, big_endian),
+            ScalarType::Uint32 => encode!(self.out, u32, value, big_endian),
+            ScalarType::Float32 => encode!(self.out, f32, value, big_endian),
+            ScalarType::Float64 => encode!(self.out, f64, value, big_endian),
+        }
+    }
+
+    /// Ends an element, which only matters to the ASCII format.
+    pub fn end(&mut self) -> io::Result<()> {
+        self.separate = false;
+        match self.format {
+            PlyFormat::Ascii => writeln!(self.out),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::{self, Write};
+use crate::error::{self, ParseError, StatementError};
+use crate::parser::Token;
+use crate::parser::reader::Lines;
+use crate::ply::{PlyFormat, ScalarType};
+
+const MAGIC: &str = "ply";
+const END: &[u8] = b"\nend_header";
+const VERSION: &str = "1.0";
+const FORMAT_STATEMENT_LENGTH: usize = 2;
+const ELEMENT_STATEMENT_LENGTH: usize = 2;
+const SCALAR_STATEMENT_LENGTH: usize = 2;
+const LIST_STATEMENT_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub format: PlyFormat,
+    pub elements: Vec<Element>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub count: usize,
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub kind: PropertyKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PropertyKind {
+    Scalar(ScalarType),
+    /// Count followed by as many items.
+    List(ScalarType, ScalarType),
+}
+
+/// Parses the header at the start of `bytes`, returning it along with the
+/// offset of the data and the number of lines it spans.
+pub fn parse(bytes: &[u8], name: &str) -> Result<(Header, usize, usize), ParseError> {
+    let end = bytes.windows(END.len())
+        .position(|window| window == END)
+        .and_then(|start| {
+            let newline = bytes[start + 1..].iter().position(|&byte| byte == b'\n')?;
+            Some(start + 1 + newline + 1)
+        })
+        .ok_or_else(|| ParseError::invalid(name, "Missing 'end_header' line"))?;
+    let text = String::from_utf8_lossy(&bytes[..end]);
+    let mut header = Header {
+        format: PlyFormat::Ascii,
+        elements: vec![],
+    };
+    let mut tokens = vec![];
+    let mut lines = 0;
+    for line in Lines::plain(&text) {
+        lines = line.number;
+        Token::lex(line.text, &mut tokens);
+        let result = match line.number {
+            1 => magic_check(&tokens),
+            _ => header.statement_parse(&tokens),
+        };
+        result.map_err(|e| ParseError::syntax(name, line.number, &line.source(), e))?;
+    }
+    Ok((header, end, lines))
+}
+
+fn magic_check(tokens: &[Token]) -> Result<(), StatementError> {
+    match tokens {
+        [Token::Value { value: MAGIC, .. }] => Ok(()),
+        _ => Err(error::missing_ply_magic()),
+    }
+}
+
+impl Header {
+    fn statement_parse(&mut self, tokens: &[Token]) -> Result<(), StatementError> {
+        let Some((keyword, args)) = tokens.split_first() else {
+            return Ok(())
+        };
+        match keyword {
+            Token::Value { value: "format", .. } => self.format = format_parse(args)?,
+            Token::Value { value: "comment" | "obj_info" | "end_header", .. } => (),
+            Token::Value { value: "element", .. } => {
+                if args.len() != ELEMENT_STATEMENT_LENGTH {
+                    return Err(error::invalid_argument_length(
+                        format!("{ELEMENT_STATEMENT_LENGTH}"),
+                        args.len(),
+                    ))
+                }
+                let count = args[1].extract_value()?
+                    .parse()
+                    .map_err(|e| args[1].locate(error::custom(e)))?;
+                self.elements.push(Element {
+                    name: args[0].to_string(),
+                    count,
+                    properties: vec![],
+                });
+            }
+            Token::Value { value: "property", .. } => {
+                let element = self.elements.last_mut()
+                    .ok_or_else(|| keyword.locate(error::misplaced_property()))?;
+                element.properties.push(property_parse(args)?);
+            }
+            _ => return Err(keyword.locate(error::unexpected_token(keyword))),
+        }
+        Ok(())
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let format = match self.format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+            PlyFormat::BinaryBigEndian => "binary_big_endian",
+        };
+        writeln!(writer, "{MAGIC}")?;
+        writeln!(writer, "format {format} {VERSION}")?;
+        writeln!(writer, "comment written by scop")?;
+        for element in &self.elements {
+            writeln!(writer, "element {} {}", element.name, element.count)?;
+            for property in &element.properties {
+                match property.kind {
+                    PropertyKind::Scalar(scalar) => writeln!(writer, "property {} {}", scalar.name(), property.name)?,
+                    PropertyKind::List(count, item) => {
+                        writeln!(writer, "property list {} {} {}", count.name(), item.name(), property.name)?;
+                    }
+                }
+            }
+        }
+        writeln!(writer, "end_header")
+    }
+}
+
+fn format_parse(tokens: &[Token]) -> Result<PlyFormat, StatementError> {
+    let format = match tokens {
+        [format, Token::Value { value: VERSION, .. }] => format,
+        [_, version] => return Err(version.locate(error::unsupported_version(version))),
+        _ => return Err(error::invalid_argument_length(
+            format!("{FORMAT_STATEMENT_LENGTH}"),
+            tokens.len(),
+        )),
+    };
+    match format {
+        Token::Value { value: "ascii", .. } => Ok(PlyFormat::Ascii),
+        Token::Value { value: "binary_little_endian", .. } => Ok(PlyFormat::BinaryLittleEndian),
+        Token::Value { value: "binary_big_endian", .. } => Ok(PlyFormat::BinaryBigEndian),
+        _ => Err(format.locate(error::invalid_token(format))),
+    }
+}
+
+/// `type name` or `list count_type item_type name`.
+fn property_parse(tokens: &[Token]) -> Result<Property, StatementError> {
+    let (kind, name) = match tokens {
+        [Token::Value { value: "list", .. }, count, item, name] => {
+            (PropertyKind::List(scalar_parse(count)?, scalar_parse(item)?), name)
+        }
+        [Token::Value { value: "list", .. }, ..] => return Err(error::invalid_argument_length(
+            format!("{LIST_STATEMENT_LENGTH}"),
+            tokens.len(),
+        )),
+        [scalar, name] => (PropertyKind::Scalar(scalar_parse(scalar)?), name),
+        _ => return Err(error::invalid_argument_length(
+            format!("{SCALAR_STATEMENT_LENGTH} or {LIST_STATEMENT_LENGTH}"),
+            tokens.len(),
+        )),
+    };
+    Ok(Property {
+        name: name.to_string(),
+        kind,
+    })
+}
+
+fn scalar_parse(token: &Token) -> Result<ScalarType, StatementError> {
+    token.extract_value()
+        .ok()
+        .and_then(ScalarType::parse)
+        .ok_or_else(|| token.locate(error::unknown_type(token)))
+}
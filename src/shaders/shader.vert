@@ -1,20 +1,29 @@
 #version 450
 
-layout(location = 0) out vec3 fragColor;
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec3 color;
+
+layout(push_constant) uniform PushConstants {
+    mat4 mvp;
+    mat4 model;
+} push_constants;
 
-vec2 positions[3] = vec2[](
-    vec2(0.0, -0.5),
-    vec2(0.5, 0.5),
-    vec2(-0.5, 0.5)
-);
+layout(location = 0) out vec3 fragColor;
 
-vec3 colors[3] = vec3[](
-    vec3(1.0, 0.0, 0.0),
-    vec3(0.0, 1.0, 0.0),
-    vec3(0.0, 0.0, 1.0)
-);
+const vec3 LIGHT_DIRECTION = normalize(vec3(0.4, 0.6, 1.0));
+const float AMBIENT = 0.25;
+const float POINT_SIZE = 1.0;
 
 void main() {
-    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
-    fragColor = colors[gl_VertexIndex];
+    gl_Position = push_constants.mvp * vec4(position, 1.0);
+    gl_PointSize = POINT_SIZE;
+    // Lines and points have no normal and are left unlit.
+    if (dot(normal, normal) == 0.0) {
+        fragColor = color;
+        return;
+    }
+    vec3 world_normal = normalize(mat3(push_constants.model) * normal);
+    float diffuse = abs(dot(world_normal, LIGHT_DIRECTION));
+    fragColor = color * (AMBIENT + (1.0 - AMBIENT) * diffuse);
 }
\ No newline at end of file
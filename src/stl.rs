@@ -0,0 +1,118 @@
+mod ascii;
+mod binary;
+
+use std::fs;
+use std::io::{self, Write};
+use ahash::{HashMap, HashMapExt};
+use crate::error::ParseError;
+use crate::object::vector::{self, Vector3};
+use crate::object::{Corner, Face, Object, VertexNormal, Vertex};
+
+/// Encoding of an STL file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StlFormat {
+    Ascii,
+    /// The smaller and more common one.
+    #[default]
+    Binary,
+}
+
+/// Parses the STL file at `path`, see [`parse_bytes`].
+pub fn parse(path: &str) -> Result<Object, ParseError> {
+    let bytes = fs::read(path)
+        .map_err(|e| ParseError::io(path, e))?;
+    parse_bytes(&bytes, path)
+}
+
+/// Parses an ASCII or binary STL file, errors being reported under `name`.
+///
+/// The vertices shared by several facets are welded into one, giving an
+/// indexed mesh. The normal of each facet is kept as the normal of its
+/// corners, unless it is null. Every `solid` of an ASCII file starts an
+/// object named after it.
+pub fn parse_bytes(bytes: &[u8], name: &str) -> Result<Object, ParseError> {
+    match binary::detect(bytes) {
+        true => binary::parse(bytes, name),
+        false => ascii::parse(&String::from_utf8_lossy(bytes), name),
+    }
+}
+
+/// Writes the faces of `object` as STL facets, splitting them into triangles
+/// whose normals are computed from their vertices. Lines and points have no
+/// STL counterpart and are left out.
+pub fn write(object: &Object, writer: impl Write, format: StlFormat) -> io::Result<()> {
+    let name = object.objects.first().map_or("", |range| range.name.as_str());
+    let facets = facets(object);
+    match format {
+        StlFormat::Ascii => ascii::write(&facets, name, writer),
+        StlFormat::Binary => binary::write(&facets, writer),
+    }
+}
+
+/// Normal and vertices of a triangle.
+struct Facet {
+    normal: Vector3,
+    vertices: [Vector3; 3],
+}
+
+fn facets(object: &Object) -> Vec<Facet> {
+    object.faces.iter()
+        .flat_map(|face| face.triangulate(&object.vertices))
+        .map(|triangle| {
+            let vertices: [Vector3; 3] = std::array::from_fn(|i| {
+                object.vertices[triangle.corners[i].vertex].position()
+            });
+            let [a, b, c] = vertices;
+            Facet {
+                normal: vector::normalize(vector::cross(vector::sub(b, a), vector::sub(c, a))),
+                vertices,
+            }
+        })
+        .collect()
+}
+
+/// Builds an object out of facets, storing each distinct position and normal
+/// once.
+struct Welder {
+    object: Object,
+    vertices: HashMap<[u32; 3], usize>,
+    normals: HashMap<[u32; 3], usize>,
+}
+
+impl Welder {
+    fn new() -> Self {
+        Self {
+            object: Object::new(),
+            vertices: HashMap::new(),
+            normals: HashMap::new(),
+        }
+    }
+
+    fn facet(&mut self, normal: Vector3, vertices: &[Vector3]) {
+        let normal = match normal.iter().all(|&component| component == 0.0) {
+            true => None,
+            false => Some(*self.normals.entry(key(normal)).or_insert_with(|| {
+                self.object.add_vertex_normal(VertexNormal::new(normal[0], normal[1], normal[2]));
+                self.object.vertices_normal.len() - 1
+            })),
+        };
+        let mut face = Face::new();
+        for &position in vertices {
+            let vertex = *self.vertices.entry(key(position)).or_insert_with(|| {
+                self.object.add_vertex(Vertex::new(position[0], position[1], position[2], None));
+                self.object.vertices.len() - 1
+            });
+            face.corners.push(Corner {
+                vertex,
+                texture: None,
+                normal,
+            });
+        }
+        self.object.add_face(face);
+    }
+}
+
+/// Bits of the components, adding zero to make `-0.0` weld with `0.0`.
+fn key(vector: Vector3) -> [u32; 3] {
+    vector.map(|component| (component + 0.0).to_bits())
+}
@@ -0,0 +1,124 @@
+use std::io::{self, Write};
+use crate::error::{self, ParseError, StatementError};
+use crate::object::Object;
+use crate::object::vector::Vector3;
+use crate::parser::Token;
+use crate::parser::reader::Lines;
+use crate::stl::{Facet, Welder};
+
+const VECTOR_STATEMENT_LENGTH: usize = 3;
+const FACET_MIN_VERTICES: usize = 3;
+
+/// Statement expected next, following the nesting of the format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    Solid,
+    Facet,
+    OuterLoop,
+    Vertex,
+    EndFacet,
+}
+
+struct AsciiParser {
+    welder: Welder,
+    expected: Expected,
+    normal: Vector3,
+    vertices: Vec<Vector3>,
+}
+
+/// Parses `solid`, `facet normal`, `outer loop`, `vertex`, `endloop`,
+/// `endfacet` and `endsolid` statements. Facets may have more than three
+/// vertices.
+pub fn parse(source: &str, name: &str) -> Result<Object, ParseError> {
+    let mut parser = AsciiParser {
+        welder: Welder::new(),
+        expected: Expected::Solid,
+        normal: [0.0; 3],
+        vertices: vec![],
+    };
+    let mut tokens = vec![];
+    let mut last = None;
+    for line in Lines::plain(source) {
+        Token::lex(line.text, &mut tokens);
+        parser.statement_parse(&tokens)
+            .map_err(|e| ParseError::syntax(name, line.number, &line.source(), e))?;
+        if !tokens.is_empty() {
+            last = Some(line);
+        }
+    }
+    if parser.expected != Expected::Solid {
+        return Err(match last {
+            Some(line) => ParseError::syntax(name, line.number, &line.source(), error::unterminated_solid()),
+            None => ParseError::invalid(name, "Empty STL file"),
+        });
+    }
+    Ok(parser.welder.object)
+}
+
+impl AsciiParser {
+    fn statement_parse(&mut self, tokens: &[Token]) -> Result<(), StatementError> {
+        let Some((keyword, args)) = tokens.split_first() else {
+            return Ok(())
+        };
+        match (self.expected, keyword) {
+            (Expected::Solid, Token::Value { value: "solid", .. }) => {
+                let name = args.iter().map(Token::to_string).collect::<Vec<_>>().join(" ");
+                if !name.is_empty() {
+                    self.welder.object.begin_object(name);
+                }
+                self.expected = Expected::Facet;
+            }
+            (Expected::Facet, Token::Value { value: "facet", .. }) => {
+                match args.split_first() {
+                    Some((Token::Value { value: "normal", .. }, args)) => self.normal = vector_parse(args)?,
+                    _ => return Err(error::custom("Expected 'facet normal <i> <j> <k>'")),
+                }
+                self.expected = Expected::OuterLoop;
+            }
+            (Expected::Facet, Token::Value { value: "endsolid", .. }) => self.expected = Expected::Solid,
+            (Expected::OuterLoop, Token::Value { value: "outer", .. }) => {
+                if !matches!(args, [Token::Value { value: "loop", .. }]) {
+                    return Err(error::custom("Expected 'outer loop'"));
+                }
+                self.expected = Expected::Vertex;
+            }
+            (Expected::Vertex, Token::Value { value: "vertex", .. }) => self.vertices.push(vector_parse(args)?),
+            (Expected::Vertex, Token::Value { value: "endloop", .. }) => {
+                if self.vertices.len() < FACET_MIN_VERTICES {
+                    return Err(error::invalid_facet(self.vertices.len()));
+                }
+                self.welder.facet(self.normal, &self.vertices);
+                self.vertices.clear();
+                self.expected = Expected::EndFacet;
+            }
+            (Expected::EndFacet, Token::Value { value: "endfacet", .. }) => self.expected = Expected::Facet,
+            _ => return Err(keyword.locate(error::unexpected_token(keyword))),
+        }
+        Ok(())
+    }
+}
+
+fn vector_parse(tokens: &[Token]) -> Result<Vector3, StatementError> {
+    if tokens.len() != VECTOR_STATEMENT_LENGTH {
+        return Err(error::invalid_argument_length(
+            format!("{VECTOR_STATEMENT_LENGTH}"),
+            tokens.len(),
+        ))
+    }
+    Ok([tokens[0].parse_f32()?, tokens[1].parse_f32()?, tokens[2].parse_f32()?])
+}
+
+pub fn write(facets: &[Facet], name: &str, mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "solid {name}")?;
+    for facet in facets {
+        let [i, j, k] = facet.normal;
+        writeln!(writer, "  facet normal {i:e} {j:e} {k:e}")?;
+        writeln!(writer, "    outer loop")?;
+        for [x, y, z] in facet.vertices {
+            writeln!(writer, "      vertex {x:e} {y:e} {z:e}")?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid {name}")
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+use crate::error::ParseError;
+use crate::object::Object;
+use crate::object::vector::Vector3;
+use crate::stl::{Facet, Welder};
+
+const HEADER_LENGTH: usize = 80;
+const HEADER: &[u8] = b"Binary STL written by scop";
+/// Header and triangle count.
+const PREAMBLE_LENGTH: usize = HEADER_LENGTH + 4;
+/// Normal, vertices and attribute byte count.
+const FACET_LENGTH: usize = 12 * 4 + 2;
+const ASCII_KEYWORD: &[u8] = b"solid";
+
+/// Whether `bytes` hold a binary file. Binary headers often start with
+/// `solid` as well, so the length matching the triangle count decides first.
+pub fn detect(bytes: &[u8]) -> bool {
+    if let Some(count) = bytes.get(HEADER_LENGTH..PREAMBLE_LENGTH) {
+        let count = u32::from_le_bytes(count.try_into().unwrap()) as u64;
+        if PREAMBLE_LENGTH as u64 + count * FACET_LENGTH as u64 == bytes.len() as u64 {
+            return true;
+        }
+    }
+    let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(bytes.len());
+    !bytes[start..].starts_with(ASCII_KEYWORD)
+}
+
+pub fn parse(bytes: &[u8], name: &str) -> Result<Object, ParseError> {
+    let count = bytes.get(HEADER_LENGTH..PREAMBLE_LENGTH)
+        .ok_or_else(|| ParseError::invalid(name, format!("File of {} bytes is too short for an STL header", bytes.len())))?;
+    let count = u32::from_le_bytes(count.try_into().unwrap()) as usize;
+    let facets = &bytes[PREAMBLE_LENGTH..];
+    if facets.len() / FACET_LENGTH < count {
+        let found = facets.len() / FACET_LENGTH;
+        return Err(ParseError::invalid(name, format!("Expected {count} facets, found {found}")));
+    }
+    let mut welder = Welder::new();
+    for facet in facets.chunks_exact(FACET_LENGTH).take(count) {
+        let vectors: [Vector3; 4] = std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                let offset = (i * 3 + j) * 4;
+                f32::from_le_bytes(facet[offset..offset + 4].try_into().unwrap())
+            })
+        });
+        welder.facet(vectors[0], &vectors[1..]);
+    }
+    Ok(welder.object)
+}
+
+pub fn write(facets: &[Facet], mut writer: impl Write) -> io::Result<()> {
+    let count = u32::try_from(facets.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many facets for a binary STL file"))?;
+    let mut header = [0; HEADER_LENGTH];
+    header[..HEADER.len()].copy_from_slice(HEADER);
+    writer.write_all(&header)?;
+    writer.write_all(&count.to_le_bytes())?;
+    for facet in facets {
+        let mut bytes = [0; FACET_LENGTH];
+        let components = std::iter::once(&facet.normal).chain(&facet.vertices).flatten();
+        for (chunk, component) in bytes.chunks_exact_mut(4).zip(components) {
+            chunk.copy_from_slice(&component.to_le_bytes());
+        }
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,245 @@
+mod material_library;
+
+use std::fmt;
+use std::io::{self, Write};
+use crate::object::{Corner, Face, Object, SMOOTHING_GROUP_OFF};
+
+pub use material_library::write_material_library;
+
+/// How face, line and point statements refer to the vertex data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexMode {
+    /// 1-based positions in the lists.
+    #[default]
+    Absolute,
+    /// Negative positions from the end of the lists, which the writer
+    /// declares in full before any element.
+    Relative,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Decimals written for each number, `None` writing the shortest text
+    /// that parses back to the same value.
+    pub precision: Option<usize>,
+    pub indices: IndexMode,
+    /// Splits every face into triangles, see [`Object::triangulate`].
+    pub triangulate: bool,
+}
+
+/// Writes `object` as OBJ statements: the `mtllib` statements, the vertex
+/// data, then the elements along with the `o`, `g`, `s` and `usemtl`
+/// statements that rebuild their names, smoothing groups and materials.
+///
+/// Free-form curves and surfaces are written as the lines and faces they were
+/// tessellated into. `usemtl` cannot clear a material, so lines and points
+/// without one are written before the others, and faces without one must come
+/// first, as the parser produces them, an `InvalidInput` error being returned
+/// otherwise. Materials are declared in the order of the list, including
+/// those that no element uses.
+pub fn write(object: &Object, writer: impl Write, options: &WriteOptions) -> io::Result<()> {
+    let mut writer = ObjWriter {
+        out: writer,
+        object,
+        options,
+        material: None,
+        declared: 0,
+        smoothing_group: SMOOTHING_GROUP_OFF,
+    };
+    writer.write()
+}
+
+struct ObjWriter<'a, W> {
+    out: W,
+    object: &'a Object,
+    options: &'a WriteOptions,
+    /// Material and smoothing group set by the statements written so far.
+    material: Option<usize>,
+    smoothing_group: u32,
+    /// Materials named by a `usemtl` statement so far, see [`ObjWriter::declare`].
+    declared: usize,
+}
+
+impl<W: Write> ObjWriter<'_, W> {
+    fn write(&mut self) -> io::Result<()> {
+        let object = self.object;
+        for library in &object.material_libraries {
+            writeln!(self.out, "mtllib {library}")?;
+        }
+        for vertex in &object.vertices {
+            write!(self.out, "v {} {} {}", self.number(vertex.x), self.number(vertex.y), self.number(vertex.z))?;
+            if vertex.w != 1.0 {
+                write!(self.out, " {}", self.number(vertex.w))?;
+            }
+            if let Some([r, g, b]) = vertex.color {
+                write!(self.out, " {} {} {}", self.number(r), self.number(g), self.number(b))?;
+            }
+            writeln!(self.out)?;
+        }
+        for texture in &object.vertices_texture {
+            write!(self.out, "vt {}", self.number(texture.u))?;
+            if let Some(v) = texture.v {
+                write!(self.out, " {}", self.number(v))?;
+                if let Some(w) = texture.w {
+                    write!(self.out, " {}", self.number(w))?;
+                }
+            }
+            writeln!(self.out)?;
+        }
+        for normal in &object.vertices_normal {
+            writeln!(self.out, "vn {} {} {}", self.number(normal.i), self.number(normal.j), self.number(normal.k))?;
+        }
+        for parameter in &object.vertices_parameter {
+            write!(self.out, "vp {}", self.number(parameter.u))?;
+            if let Some(v) = parameter.v {
+                write!(self.out, " {}", self.number(v))?;
+                if parameter.w != 1.0 {
+                    write!(self.out, " {}", self.number(parameter.w))?;
+                }
+            }
+            writeln!(self.out)?;
+        }
+        self.lines_and_points_write(false)?;
+        self.faces_write()?;
+        self.lines_and_points_write(true)?;
+        self.declare(object.materials.len())
+    }
+
+    /// Faces, preceded by the statements opening the ranges that start at
+    /// each of them. Ranges left empty at the end of the file come last.
+    fn faces_write(&mut self) -> io::Result<()> {
+        let object = self.object;
+        for (i, face) in object.faces.iter().enumerate() {
+            self.ranges_write(i)?;
+            self.material_write(face.material)?;
+            if face.smoothing_group != self.smoothing_group {
+                self.smoothing_group = face.smoothing_group;
+                match face.smoothing_group {
+                    SMOOTHING_GROUP_OFF => writeln!(self.out, "s off")?,
+                    group => writeln!(self.out, "s {group}")?,
+                }
+            }
+            match self.options.triangulate {
+                true => {
+                    for triangle in face.triangulate(&object.vertices) {
+                        self.face_write(&triangle)?;
+                    }
+                }
+                false => self.face_write(face)?,
+            }
+        }
+        self.ranges_write(object.faces.len())
+    }
+
+    fn ranges_write(&mut self, face: usize) -> io::Result<()> {
+        let object = self.object;
+        for range in object.objects.iter().filter(|range| range.faces.start == face) {
+            writeln!(self.out, "o {}", range.name)?;
+        }
+        let mut groups = object.groups.iter()
+            .filter(|range| range.faces.start == face)
+            .map(|range| range.name.as_str())
+            .peekable();
+        if groups.peek().is_some() {
+            writeln!(self.out, "g {}", groups.collect::<Vec<_>>().join(" "))?;
+        }
+        Ok(())
+    }
+
+    fn face_write(&mut self, face: &Face) -> io::Result<()> {
+        write!(self.out, "f")?;
+        for corner in &face.corners {
+            write!(self.out, " {}", self.corner(corner))?;
+        }
+        writeln!(self.out)
+    }
+
+    /// Lines and points that have a material if `assigned`, the others
+    /// otherwise.
+    fn lines_and_points_write(&mut self, assigned: bool) -> io::Result<()> {
+        let object = self.object;
+        for line in object.lines.iter().filter(|line| line.material.is_some() == assigned) {
+            self.material_write(line.material)?;
+            write!(self.out, "l")?;
+            for corner in &line.corners {
+                write!(self.out, " {}", self.corner(corner))?;
+            }
+            writeln!(self.out)?;
+        }
+        for point in object.points.iter().filter(|point| point.material.is_some() == assigned) {
+            self.material_write(point.material)?;
+            write!(self.out, "p")?;
+            for &vertex in &point.vertices {
+                write!(self.out, " {}", self.index(vertex, object.vertices.len()))?;
+            }
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+
+    fn material_write(&mut self, material: Option<usize>) -> io::Result<()> {
+        if material.is_none() && self.material.is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Element without a material after a material was set"));
+        }
+        if let Some(index) = material.filter(|_| material != self.material) {
+            self.declare(index)?;
+            self.material = material;
+            writeln!(self.out, "usemtl {}", self.object.materials[index].name)?;
+            self.declared = self.declared.max(index + 1);
+        }
+        Ok(())
+    }
+
+    /// Names the materials preceding `end` that no statement has named yet,
+    /// as the parser lists materials in the order `usemtl` first names them.
+    fn declare(&mut self, end: usize) -> io::Result<()> {
+        for material in &self.object.materials[self.declared.min(end)..end] {
+            writeln!(self.out, "usemtl {}", material.name)?;
+        }
+        self.declared = self.declared.max(end);
+        Ok(())
+    }
+
+    fn number(&self, value: f32) -> Number {
+        Number {
+            value,
+            precision: self.options.precision,
+        }
+    }
+
+    fn index(&self, index: usize, len: usize) -> isize {
+        match self.options.indices {
+            IndexMode::Absolute => index as isize + 1,
+            IndexMode::Relative => index as isize - len as isize,
+        }
+    }
+
+    /// `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+    fn corner(&self, corner: &Corner) -> String {
+        let object = self.object;
+        let vertex = self.index(corner.vertex, object.vertices.len());
+        let texture = corner.texture.map(|texture| self.index(texture, object.vertices_texture.len()));
+        let normal = corner.normal.map(|normal| self.index(normal, object.vertices_normal.len()));
+        match (texture, normal) {
+            (None, None) => format!("{vertex}"),
+            (Some(texture), None) => format!("{vertex}/{texture}"),
+            (None, Some(normal)) => format!("{vertex}//{normal}"),
+            (Some(texture), Some(normal)) => format!("{vertex}/{texture}/{normal}"),
+        }
+    }
+}
+
+/// Number written with the precision of the options.
+struct Number {
+    value: f32,
+    precision: Option<usize>,
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.precision {
+            Some(precision) => write!(f, "{:.precision$}", self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{self, Write};
+use crate::object::{ImageChannel, Material, TextureMap};
+use crate::writer::{Number, WriteOptions};
+
+/// Writes `materials` as a material library, leaving out the properties they
+/// do not set and the texture map options left to their default.
+pub fn write_material_library(materials: &[Material], mut writer: impl Write, options: &WriteOptions) -> io::Result<()> {
+    let number = |value| Number {
+        value,
+        precision: options.precision,
+    };
+    for (i, material) in materials.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "newmtl {}", material.name)?;
+        let colors = [
+            ("Ka", material.ambient),
+            ("Kd", material.diffuse),
+            ("Ks", material.specular),
+            ("Ke", material.emissive),
+            ("Tf", material.transmission_filter),
+        ];
+        for (keyword, color) in colors {
+            if let Some([r, g, b]) = color {
+                writeln!(writer, "{keyword} {} {} {}", number(r), number(g), number(b))?;
+            }
+        }
+        let scalars = [
+            ("Ns", material.specular_exponent),
+            ("Ni", material.optical_density),
+            ("d", material.dissolve),
+            ("Pr", material.roughness),
+            ("Pm", material.metallic),
+            ("Ps", material.sheen),
+            ("Pc", material.clearcoat_thickness),
+            ("Pcr", material.clearcoat_roughness),
+            ("aniso", material.anisotropy),
+            ("anisor", material.anisotropy_rotation),
+        ];
+        for (keyword, scalar) in scalars {
+            if let Some(scalar) = scalar {
+                writeln!(writer, "{keyword} {}", number(scalar))?;
+            }
+        }
+        if let Some(illumination) = material.illumination {
+            writeln!(writer, "illum {illumination}")?;
+        }
+        let maps = [
+            ("map_Ka", &material.ambient_map),
+            ("map_Kd", &material.diffuse_map),
+            ("map_Ks", &material.specular_map),
+            ("map_Ns", &material.specular_exponent_map),
+            ("map_d", &material.dissolve_map),
+            ("map_Ke", &material.emissive_map),
+            ("bump", &material.bump_map),
+            ("disp", &material.displacement_map),
+            ("decal", &material.decal_map),
+            ("map_Pr", &material.roughness_map),
+            ("map_Pm", &material.metallic_map),
+            ("norm", &material.normal_map),
+        ];
+        let maps = maps.into_iter()
+            .filter_map(|(keyword, map)| Some((keyword, map.as_ref()?)))
+            .chain(material.reflection_maps.iter().map(|map| ("refl", map)));
+        for (keyword, map) in maps {
+            write!(writer, "{keyword}")?;
+            texture_map_write(map, &mut writer, &number)?;
+            writeln!(writer, " {}", map.file)?;
+        }
+    }
+    Ok(())
+}
+
+fn texture_map_write(map: &TextureMap, writer: &mut impl Write, number: &impl Fn(f32) -> Number) -> io::Result<()> {
+    let default = TextureMap::new(String::new());
+    let switch = |on| if on { "on" } else { "off" };
+    let switches = [
+        ("-blendu", map.blend_u, default.blend_u),
+        ("-blendv", map.blend_v, default.blend_v),
+        ("-cc", map.color_correction, default.color_correction),
+        ("-clamp", map.clamp, default.clamp),
+    ];
+    for (option, value, default) in switches {
+        if value != default {
+            write!(writer, " {option} {}", switch(value))?;
+        }
+    }
+    if map.bump_multiplier != default.bump_multiplier {
+        write!(writer, " -bm {}", number(map.bump_multiplier))?;
+    }
+    if let Some(boost) = map.boost {
+        write!(writer, " -boost {}", number(boost))?;
+    }
+    if let Some(channel) = map.channel {
+        let channel = match channel {
+            ImageChannel::Red => "r",
+            ImageChannel::Green => "g",
+            ImageChannel::Blue => "b",
+            ImageChannel::Matte => "m",
+            ImageChannel::Luminance => "l",
+            ImageChannel::Depth => "z",
+        };
+        write!(writer, " -imfchan {channel}")?;
+    }
+    if let Some(resolution) = map.resolution {
+        write!(writer, " -texres {resolution}")?;
+    }
+    if let Some(projection) = &map.projection {
+        write!(writer, " -type {projection}")?;
+    }
+    if map.range != default.range {
+        write!(writer, " -mm {} {}", number(map.range[0]), number(map.range[1]))?;
+    }
+    let vectors = [
+        ("-o", map.offset, default.offset),
+        ("-s", map.scale, default.scale),
+        ("-t", map.turbulence, default.turbulence),
+    ];
+    for (option, [u, v, w], default) in vectors {
+        if [u, v, w] != default {
+            write!(writer, " {option} {} {} {}", number(u), number(v), number(w))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use scop::cache::{self, CacheLocation};
+use scop::error::CacheError;
+use scop::parser::{self, ParseMode, ParseOptions};
+use common::TempDir;
+
+mod common;
+
+const SOURCE: &str = "mtllib cube.mtl\nusemtl red\n\
+    v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\n\
+    g side\nf 1/1/1 2/1/1 3/1/1 4/1/1\nl 1 3\np 2\n\
+    cstype bezier\ndeg 1 1\nsurf 0 1 0 1 1 2 4 3\nparm u 0 1\nparm v 0 1\nend\n";
+
+/// Directory holding `cube.obj` and `cube.mtl`, unique to the test.
+fn sources(test: &str) -> (TempDir, String) {
+    let directory = TempDir::new("cache", test);
+    fs::write(directory.join("cube.mtl"), "newmtl red\nKd 1 0 0\n").unwrap();
+    fs::write(directory.join("cube.obj"), SOURCE).unwrap();
+    let path = directory.join("cube.obj").to_string_lossy().into_owned();
+    (directory, path)
+}
+
+#[test]
+fn cache_round_trips_and_goes_stale() {
+    let (directory, path) = sources("stale");
+    let options = ParseOptions::default();
+    let object = parser::parse_with(&path, &options).unwrap().object;
+    for location in [CacheLocation::NextToSource, CacheLocation::Directory(directory.join("cache"))] {
+        cache::store(&object, &path, &location, &options).unwrap();
+        assert_eq!(cache::load(&path, &location, &options).unwrap(), object);
+        let coarser = ParseOptions { resolution: 2, ..options };
+        assert!(matches!(cache::load(&path, &location, &coarser), Err(CacheError::Stale { .. })));
+    }
+    fs::write(directory.join("cube.mtl"), "newmtl red\nKd 0 1 0\n").unwrap();
+    for location in [CacheLocation::NextToSource, CacheLocation::Directory(directory.join("cache"))] {
+        assert!(matches!(cache::load(&path, &location, &options), Err(CacheError::Stale { .. })));
+    }
+}
+
+#[test]
+fn cache_rejects_damaged_files() {
+    let (_directory, path) = sources("corrupt");
+    let options = ParseOptions::default();
+    let location = CacheLocation::NextToSource;
+    let object = parser::parse_with(&path, &options).unwrap().object;
+    cache::store(&object, &path, &location, &options).unwrap();
+    let cache_path = cache::cache_path(&path, &location).unwrap();
+    let bytes = fs::read(&cache_path).unwrap();
+    for position in (0..bytes.len()).step_by(97) {
+        let mut damaged = bytes.clone();
+        damaged[position] ^= 0x10;
+        fs::write(&cache_path, &damaged).unwrap();
+        assert!(cache::load(&path, &location, &options).is_err(), "byte {position}");
+    }
+    for length in [0, 7, bytes.len() / 2, bytes.len() - 1] {
+        fs::write(&cache_path, &bytes[..length]).unwrap();
+        assert!(matches!(cache::load(&path, &location, &options), Err(CacheError::Corrupt { .. })));
+    }
+}
+
+#[test]
+fn cache_keeps_the_parse_mode() {
+    let (_directory, path) = sources("mode");
+    fs::write(&path, format!("{SOURCE}shade flat\n")).unwrap();
+    let strict = ParseOptions::default();
+    let lenient = ParseOptions { mode: ParseMode::Lenient, ..strict };
+    assert!(parser::parse_with(&path, &strict).is_err());
+    let parsed = parser::parse_with(&path, &lenient).unwrap();
+    assert_eq!(parsed.warnings.len(), 1);
+    let location = CacheLocation::NextToSource;
+    cache::store(&parsed.object, &path, &location, &lenient).unwrap();
+    assert_eq!(cache::load(&path, &location, &lenient).unwrap(), parsed.object);
+    assert!(matches!(cache::load(&path, &location, &strict), Err(CacheError::Stale { .. })));
+}
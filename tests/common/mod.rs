@@ -0,0 +1,32 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// Directory unique to a test, removed along with its content once dropped,
+/// whether the test passes or not.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates the directory of `test` in `suite`, emptied of anything a
+    /// previous run left behind.
+    pub fn new(suite: &str, test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("scop-{suite}-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::Path;
+use scop::object::Object;
+use scop::error::ParseError;
+use scop::parser::{self, ParseMode, ParseOptions};
+
+/// Parses `source`, splitting each knot span into `resolution` segments.
+fn tessellate(source: &str, resolution: usize) -> Object {
+    let options = ParseOptions {
+        resolution,
+        ..ParseOptions::default()
+    };
+    parser::parse_str_with(source, Path::new("."), &options)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .object
+}
+
+/// Positions along the first polyline of `object`.
+fn polyline(object: &Object) -> Vec<[f32; 3]> {
+    object.lines[0].corners.iter()
+        .map(|corner| object.vertices[corner.vertex].position())
+        .collect()
+}
+
+fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+    assert!(actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-5), "{actual:?} != {expected:?}");
+}
+
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn de_casteljau(points: &[[f32; 3]], t: f32) -> [f32; 3] {
+    let mut points = points.to_vec();
+    while points.len() > 1 {
+        points = points.windows(2).map(|pair| lerp(pair[0], pair[1], t)).collect();
+    }
+    points[0]
+}
+
+#[test]
+fn bezier_curve_matches_de_casteljau() {
+    let control = [[0.0, 0.0, 0.0], [1.0, 2.0, 0.0], [3.0, 2.0, 1.0], [4.0, 0.0, 0.0]];
+    let mut source = String::new();
+    for [x, y, z] in control {
+        source += &format!("v {x} {y} {z}\n");
+    }
+    source += "cstype bezier\ndeg 3\ncurv 0 1 1 2 3 4\nparm u 0 1\nend\n";
+    for resolution in [2, 7] {
+        let points = polyline(&tessellate(&source, resolution));
+        assert_eq!(points.len(), resolution + 1);
+        assert_close(points[0], control[0]);
+        assert_close(points[resolution], control[3]);
+        for (i, &point) in points.iter().enumerate() {
+            assert_close(point, de_casteljau(&control, i as f32 / resolution as f32));
+        }
+    }
+    let middle = polyline(&tessellate(&source, 2))[1];
+    assert_close(middle, [2.0, 1.5, 0.375]);
+}
+
+#[test]
+fn clamped_b_spline_matches_known_values() {
+    // The x coordinates are the Greville abscissae, so that x(t) = t.
+    let source = "v 0 0 0\nv 0.33333334 2 0\nv 1 -1 0\nv 1.6666666 3 0\nv 2 1 0\n\
+        cstype bspline\ndeg 3\ncurv 0 2 1 2 3 4 5\nparm u 0 0 0 0 1 2 2 2 2\nend\n";
+    let points = polyline(&tessellate(source, 2));
+    let expected = [[0.0, 0.0], [0.5, 1.03125], [1.0, 0.75], [1.5, 1.71875], [2.0, 1.0]];
+    assert_eq!(points.len(), expected.len());
+    for (point, [x, y]) in points.into_iter().zip(expected) {
+        assert_close(point, [x, y, 0.0]);
+    }
+}
+
+#[test]
+fn rational_quarter_circle_stays_on_the_circle() {
+    let source = format!(
+        "v 1 0 0\nv 1 1 0 {}\nv 0 1 0\ncstype rat bezier\ndeg 2\ncurv 0 1 1 2 3\nparm u 0 1\nend\n",
+        std::f32::consts::FRAC_1_SQRT_2,
+    );
+    let points = polyline(&tessellate(&source, 16));
+    assert_eq!(points.len(), 17);
+    for [x, y, z] in points {
+        assert!(((x * x + y * y).sqrt() - 1.0).abs() < 1e-5 && z == 0.0, "{x} {y} {z}");
+    }
+}
+
+/// Unit square as a bilinear patch cut by `loops`, `trim` and `hole`
+/// statements over the square `curv2` spanning `[low, high]` in both
+/// parameters.
+fn plane(low: f32, high: f32, loops: &str) -> String {
+    format!(
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\n\
+        vp {low} {low}\nvp {high} {low}\nvp {high} {high}\nvp {low} {high}\n\
+        cstype bezier\ndeg 1\ncurv2 1 2 3 4 1\nparm u 0 1 2 3 4\nend\n\
+        deg 1 1\nsurf 0 1 0 1 1 2 3 4\nparm u 0 1\nparm v 0 1\n{loops}end\n"
+    )
+}
+
+/// Parameters of the centroid of each face, on the unit square.
+fn centroids(object: &Object) -> Vec<[f32; 2]> {
+    object.faces.iter()
+        .map(|face| {
+            let sum = face.corners.iter()
+                .map(|corner| object.vertices[corner.vertex].position())
+                .fold([0.0; 3], |sum, position| [0, 1, 2].map(|i| sum[i] + position[i]));
+            [sum[0] / 3.0, sum[1] / 3.0]
+        })
+        .collect()
+}
+
+#[test]
+fn trim_and_hole_loops_remove_cells() {
+    let inside = |[u, v]: [f32; 2], low: f32, high: f32| low < u && u < high && low < v && v < high;
+    // Each of the 4 by 4 cells is split into two triangles.
+    assert_eq!(tessellate(&plane(0.0, 1.0, ""), 4).faces.len(), 32);
+
+    let hole = tessellate(&plane(0.25, 0.75, "hole 0 4 1\n"), 4);
+    assert_eq!(hole.faces.len(), 32 - 8);
+    assert!(centroids(&hole).into_iter().all(|centroid| !inside(centroid, 0.25, 0.75)));
+
+    let trim = tessellate(&plane(0.25, 0.75, "trim 0 4 1\n"), 4);
+    assert_eq!(trim.faces.len(), 8);
+    assert!(centroids(&trim).into_iter().all(|centroid| inside(centroid, 0.25, 0.75)));
+
+    let both = tessellate(&plane(0.25, 0.75, "trim 0 4 1\nhole 0 4 1\n"), 4);
+    assert!(both.faces.is_empty());
+}
+
+/// Normal of the first corner of each face, along with the one its winding
+/// gives.
+fn normals(object: &Object) -> Vec<([f32; 3], [f32; 3])> {
+    object.faces.iter()
+        .map(|face| {
+            let normal = object.vertices_normal[face.corners[0].normal.unwrap()];
+            let [a, b, c] = [0, 1, 2].map(|i| object.vertices[face.corners[i].vertex].position());
+            let (ab, ac) = ([0, 1, 2].map(|i| b[i] - a[i]), [0, 1, 2].map(|i| c[i] - a[i]));
+            let winding = [
+                ab[1] * ac[2] - ab[2] * ac[1],
+                ab[2] * ac[0] - ab[0] * ac[2],
+                ab[0] * ac[1] - ab[1] * ac[0],
+            ];
+            ([normal.i, normal.j, normal.k], winding)
+        })
+        .collect()
+}
+
+#[test]
+fn reversed_range_flips_normals() {
+    for (range, z) in [("0 1", 1.0), ("1 0", -1.0)] {
+        let source = format!("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\n\
+            cstype bezier\ndeg 1 1\nsurf {range} 0 1 1 2 3 4\nparm u 0 1\nparm v 0 1\nend\n");
+        let object = tessellate(&source, 2);
+        assert_eq!(object.faces.len(), 8);
+        for (normal, winding) in normals(&object) {
+            assert_close(normal, [0.0, 0.0, z]);
+            assert!(winding[2] * z > 0.0, "{winding:?} for range {range}");
+        }
+    }
+}
+
+/// Line and message of the error parsing `source` gives.
+fn error(source: &str) -> (usize, String) {
+    match parser::parse_str(source, Path::new(".")) {
+        Err(ParseError::Syntax { line, message, .. }) => (line, message),
+        result => panic!("{source:?} gave {result:?}"),
+    }
+}
+
+#[test]
+fn non_finite_parameters_are_rejected() {
+    let line = "v 0 0 0\nv 1 0 0\ncstype bspline\ndeg 1\n";
+    let square = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvp 0 0\nvp 1 0\nvp 1 1\ncstype bezier\ndeg 1 1\n\
+        curv2 1 2 3 1\nparm u 0 1 2 3\nend\n";
+    let sources = [
+        (format!("{line}curv 0 1 1 2\nparm u 0 0 nan 1\nend\n"), 7),
+        (format!("{line}curv 0 1 1 2\nparm u 0 0 inf 1 1\nend\n"), 7),
+        (format!("{line}curv 0 nan 1 2\nparm u 0 0 1 1\nend\n"), 5),
+        (format!("{line}curv -inf 1 1 2\nparm u 0 0 1 1\nend\n"), 5),
+        (format!("{square}surf 0 1 nan 1 1 2 3 4\nparm u 0 1\nparm v 0 1\nend\n"), 13),
+        (format!("{square}surf 0 1 0 1 1 2 3 4\nparm u 0 1\nparm v 0 1\nhole 0 nan 1\nend\n"), 16),
+    ];
+    for (source, line) in sources {
+        let (actual, message) = error(&source);
+        assert_eq!(actual, line, "{source}");
+        assert!(message.starts_with("Global parameters must be finite"), "{message}");
+    }
+}
+
+#[test]
+fn degrees_are_bounded() {
+    let (line, message) = error("v 0 0 0\nv 1 0 0\ncstype bezier\ndeg 100000000\ncurv 0 1 1 2\nparm u 0 1\nend\n");
+    assert_eq!((line, message.as_str()), (7, "Invalid degree for the curve type"));
+    let (_, message) = error("v 0 0 0\nv 1 0 0\ncstype taylor\ndeg 1 100000000\nsurf 0 1 0 1 1 2\nparm u 0 1\nparm v 0 1\nend\n");
+    assert_eq!(message, "Invalid degree for the curve type");
+}
+
+#[test]
+fn elements_without_end_are_reported() {
+    let sources = [
+        ("v 0 0 0\nv 1 0 0\ncstype bezier\ndeg 1\ncurv 0 1 1 2\nparm u 0 1\n# no end\n", 5),
+        ("vp 0 0\nvp 1 0\ncstype bezier\ndeg 1\ncurv2 1 2\nparm u 0 1\n", 5),
+        ("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\ncstype bezier\ndeg 1 1\nsurf 0 1 0 1 1 2 3 4\n", 7),
+    ];
+    for (source, line) in sources {
+        assert_eq!(error(source).0, line, "{source}");
+        for threads in [1, 3] {
+            let options = ParseOptions {
+                mode: ParseMode::Lenient,
+                threads,
+                ..ParseOptions::default()
+            };
+            let parsed = parser::parse_str_with(source, Path::new("."), &options).unwrap();
+            assert_eq!(parsed.warnings.len(), 1, "{source}");
+            match &parsed.warnings[0].0 {
+                ParseError::Syntax { line: actual, column: 1, .. } => assert_eq!(*actual, line),
+                warning => panic!("unexpected warning {warning:?}"),
+            }
+            assert!(parsed.object.lines.is_empty() && parsed.object.faces.is_empty());
+        }
+        let sequential = parser::parse_str(source, Path::new(".")).unwrap_err();
+        let parallel = parser::parse_str_parallel(source, Path::new("."), 3).unwrap_err();
+        assert_eq!(parallel.to_string(), sequential.to_string());
+    }
+}
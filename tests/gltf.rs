@@ -0,0 +1,153 @@
+use std::path::Path;
+use serde_json::Value;
+use scop::gltf;
+use scop::object::{Object, TextureMap};
+use scop::parser;
+
+/// Quad split over two groups and two materials, with texture coordinates
+/// and normals on every corner.
+const SOURCE: &str = "o panel\n\
+    v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\nv 2 1 0\n\
+    vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+    vn 0 0 2\n\
+    g left\nusemtl red\nf 1/1/1 2/2/1 3/3/1 4/4/1\n\
+    g right\nf 2/1/1 5/2/1 6/3/1\nusemtl glass\nf 2/1/1 6/3/1 3/4/1\n";
+
+fn panel() -> Object {
+    let mut object = parser::parse_str(SOURCE, Path::new(".")).unwrap();
+    let red = &mut object.materials[0];
+    red.diffuse = Some([1.0, 0.0, 0.0]);
+    red.specular_exponent = Some(198.0);
+    red.diffuse_map = Some(TextureMap::new("textures/red brick.png".to_string()));
+    let glass = &mut object.materials[1];
+    glass.dissolve = Some(0.25);
+    glass.metallic = Some(0.5);
+    glass.roughness = Some(2.0);
+    object
+}
+
+/// Reads back the accessor at `index` as rows of floats, whatever the type
+/// of its components.
+fn accessor(document: &Value, buffer: &[u8], index: &Value) -> Vec<Vec<f32>> {
+    let accessor = &document["accessors"][index.as_u64().unwrap() as usize];
+    let view = &document["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+    let offset = view["byteOffset"].as_u64().unwrap() as usize;
+    let width = match accessor["type"].as_str().unwrap() {
+        "SCALAR" => 1,
+        "VEC2" => 2,
+        "VEC3" => 3,
+        kind => panic!("unexpected accessor type {kind}"),
+    };
+    let count = accessor["count"].as_u64().unwrap() as usize;
+    assert_eq!(view["byteLength"].as_u64().unwrap() as usize, count * width * 4);
+    let values: Vec<f32> = buffer[offset..offset + count * width * 4].chunks_exact(4)
+        .map(|bytes| {
+            let bytes = bytes.try_into().unwrap();
+            match accessor["componentType"].as_u64().unwrap() {
+                5126 => f32::from_le_bytes(bytes),
+                5125 => u32::from_le_bytes(bytes) as f32,
+                kind => panic!("unexpected component type {kind}"),
+            }
+        })
+        .collect();
+    values.chunks(width).map(<[f32]>::to_vec).collect()
+}
+
+/// Checks the document against the panel, returning the group names of its
+/// primitives.
+fn check(document: &Value, buffer: &[u8]) -> Vec<String> {
+    assert_eq!(document["asset"]["version"], "2.0");
+    assert_eq!(document["buffers"][0]["byteLength"].as_u64().unwrap() as usize, buffer.len());
+    assert_eq!(document["nodes"][0]["name"], "panel");
+    let object = panel();
+    let mut triangulated = object.clone();
+    triangulated.triangulate();
+    let mut expected: Vec<_> = triangulated.faces.iter()
+        .map(|face| {
+            face.corners.iter()
+                .map(|corner| triangulated.vertices[corner.vertex].position().to_vec())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let primitives = document["meshes"][0]["primitives"].as_array().unwrap();
+    let mut triangles = vec![];
+    let mut corners = 0;
+    for primitive in primitives {
+        let attributes = &primitive["attributes"];
+        let positions = accessor(document, buffer, &attributes["POSITION"]);
+        corners = positions.len();
+        for normal in accessor(document, buffer, &attributes["NORMAL"]) {
+            assert_eq!(normal, [0.0, 0.0, 1.0]);
+        }
+        let texture = accessor(document, buffer, &attributes["TEXCOORD_0"]);
+        assert!(texture.contains(&vec![1.0, 0.0]), "{texture:?}");
+        assert!(attributes.get("COLOR_0").is_none());
+        let indices = accessor(document, buffer, &primitive["indices"]);
+        for triangle in indices.chunks(3) {
+            triangles.push(triangle.iter().map(|index| positions[index[0] as usize].clone()).collect::<Vec<_>>());
+        }
+    }
+    // Seven corners use the red material, three the glass one.
+    assert_eq!(corners, 10);
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    triangles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(triangles, expected);
+
+    let materials: Vec<_> = primitives.iter().map(|primitive| primitive["material"].as_u64().unwrap()).collect();
+    assert_eq!(materials, [0, 0, 1]);
+    let red = &document["materials"][0];
+    assert_eq!(red["name"], "red");
+    assert_eq!(red["pbrMetallicRoughness"]["baseColorFactor"], serde_json::json!([1.0, 0.0, 0.0, 1.0]));
+    assert_eq!(red["pbrMetallicRoughness"]["metallicFactor"], 0.0);
+    let roughness = red["pbrMetallicRoughness"]["roughnessFactor"].as_f64().unwrap();
+    assert!((roughness - 0.1).abs() < 1e-6, "{roughness}");
+    assert_eq!(red["pbrMetallicRoughness"]["baseColorTexture"]["index"], 0);
+    assert_eq!(document["images"][0]["uri"], "textures/red%20brick.png");
+    let glass = &document["materials"][1];
+    assert_eq!(glass["alphaMode"], "BLEND");
+    assert_eq!(glass["pbrMetallicRoughness"]["baseColorFactor"][3], 0.25);
+    assert_eq!(glass["pbrMetallicRoughness"]["metallicFactor"], 0.5);
+    assert_eq!(glass["pbrMetallicRoughness"]["roughnessFactor"], 1.0);
+
+    primitives.iter().map(|primitive| primitive["extras"]["name"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn gltf_document_and_buffer_read_back() {
+    let mut json = vec![];
+    let mut buffer = vec![];
+    gltf::write(&panel(), &mut json, &mut buffer, "panel data.bin").unwrap();
+    let document: Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(document["buffers"][0]["uri"], "panel%20data.bin");
+    assert_eq!(check(&document, &buffer), ["left", "right", "right"]);
+}
+
+#[test]
+fn glb_chunks_read_back() {
+    let mut glb = vec![];
+    gltf::write_binary(&panel(), &mut glb).unwrap();
+    assert_eq!(&glb[..4], b"glTF");
+    let word = |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap()) as usize;
+    assert_eq!(word(4), 2);
+    assert_eq!(word(8), glb.len());
+
+    let json_length = word(12);
+    assert_eq!(&glb[16..20], b"JSON");
+    assert_eq!(json_length % 4, 0);
+    let document: Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+    let bin = 20 + json_length;
+    let bin_length = word(bin);
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    assert_eq!(bin + 8 + bin_length, glb.len());
+    assert!(document["buffers"][0].get("uri").is_none());
+    let byte_length = document["buffers"][0]["byteLength"].as_u64().unwrap() as usize;
+    assert!(bin_length >= byte_length && bin_length % 4 == 0);
+    assert_eq!(check(&document, &glb[bin + 8..bin + 8 + byte_length]), ["left", "right", "right"]);
+}
+
+#[test]
+fn object_without_faces_is_rejected() {
+    let object = parser::parse_str("v 0 0 0\np 1\n", Path::new(".")).unwrap();
+    assert!(gltf::write_binary(&object, &mut vec![]).is_err());
+}
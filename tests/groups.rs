@@ -0,0 +1,58 @@
+use std::path::Path;
+use scop::object::{Grouping, NamedRange, Object, SubMesh};
+use scop::parser;
+
+/// Corners of a unit square, for triangles `1 2 3` and quads `1 2 4 3`.
+const VERTICES: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\n";
+
+fn parse(source: &str) -> Object {
+    parser::parse_str(&format!("{VERTICES}{source}"), Path::new(".")).unwrap_or_else(|e| panic!("{e}"))
+}
+
+fn ranges(object: &Object, grouping: Grouping) -> Vec<(&str, std::ops::Range<usize>)> {
+    object.ranges(grouping).iter()
+        .map(|NamedRange { name, faces }| (name.as_str(), faces.clone()))
+        .collect()
+}
+
+/// Name, `[start, end]` face ranges and face count of each sub-mesh.
+fn sub_meshes(object: &Object, grouping: Grouping) -> Vec<(&str, Vec<[usize; 2]>, usize)> {
+    object.sub_meshes(grouping).into_iter()
+        .map(|sub_mesh: SubMesh| {
+            let ranges = sub_mesh.ranges.iter().map(|range| [range.start, range.end]).collect();
+            (sub_mesh.name, ranges, sub_mesh.faces().count())
+        })
+        .collect()
+}
+
+#[test]
+fn names_cover_the_faces_that_follow() {
+    let object = parse("f 1 2 3\no first part\ng a b\nf 1 2 3\nf 1 2 3\ng\nf 1 2 3\ng a\no unused\no first part\nf 1 2 3\n");
+    assert_eq!(ranges(&object, Grouping::Object), [("first part", 1..4), ("first part", 4..5)]);
+    assert_eq!(ranges(&object, Grouping::Group), [("a", 1..3), ("b", 1..3), ("default", 3..4), ("a", 4..5)]);
+
+    assert_eq!(sub_meshes(&object, Grouping::Object), [("first part", vec![[1, 4], [4, 5]], 4)]);
+    assert_eq!(sub_meshes(&object, Grouping::Group), [
+        ("a", vec![[1, 3], [4, 5]], 3),
+        ("b", vec![[1, 3]], 2),
+        ("default", vec![[3, 4]], 1),
+    ]);
+}
+
+#[test]
+fn names_may_spell_keywords() {
+    let object = parse("o step one\nf 1 2 3\ng v end\nf 1 2 3\ng curv surf trim hole parm deg\nf 1 2 3\n");
+    assert_eq!(ranges(&object, Grouping::Object), [("step one", 0..3)]);
+    let groups: Vec<_> = object.ranges(Grouping::Group).iter().map(|range| range.name.as_str()).collect();
+    assert_eq!(groups, ["v", "end", "curv", "surf", "trim", "hole", "parm", "deg"]);
+}
+
+#[test]
+fn triangulation_remaps_the_ranges() {
+    let mut object = parse("o quad\ng a\nf 1 2 4 3\no triangle\ng a b\nf 1 2 3\ng\nf 1 2 4 3\n");
+    object.triangulate();
+    assert_eq!(object.faces.len(), 5);
+    assert_eq!(ranges(&object, Grouping::Object), [("quad", 0..2), ("triangle", 2..5)]);
+    assert_eq!(ranges(&object, Grouping::Group), [("a", 0..2), ("a", 2..3), ("b", 2..3), ("default", 3..5)]);
+    assert_eq!(sub_meshes(&object, Grouping::Group)[0], ("a", vec![[0, 2], [2, 3]], 3));
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+use scop::object::InterleavedVertex;
+use scop::parser;
+
+#[test]
+fn identical_corners_share_a_vertex() {
+    // Both triangles of the quad share corners `1/1/1` and `3/3/1`, the last
+    // face reuses position 1 with another texture coordinate.
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\nvt 0.5 0.5\nvn 0 0 1\n\
+        f 1/1/1 2/2/1 3/3/1\nf 1/1/1 3/3/1 4/4/1\nf 1/5/1 3/3/1 4/4/1\n";
+    let mesh = parser::parse_str(source, Path::new(".")).unwrap().indexed_mesh();
+    assert_eq!(mesh.triangles(), 3);
+    assert_eq!(mesh.face_ranges, [0..3, 3..6, 6..9]);
+    assert_eq!(mesh.unique_vertices(), 5);
+    assert_eq!(mesh.indices[3..5], [mesh.indices[0], mesh.indices[2]]);
+    assert_eq!(mesh.indices[7..], mesh.indices[4..6]);
+    assert_ne!(mesh.indices[6], mesh.indices[0]);
+    assert_eq!(mesh.vertices[mesh.indices[6] as usize], InterleavedVertex {
+        position: [0.0, 0.0, 0.0],
+        color: None,
+        texture: [0.5, 0.5],
+        normal: [0.0, 0.0, 1.0],
+        material: None,
+    });
+}
+
+#[test]
+fn materials_split_shared_corners() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nusemtl red\nf 1 2 3\nl 1 2\np 1\nusemtl blue\np 1\n";
+    let mesh = parser::parse_str(source, Path::new(".")).unwrap().indexed_mesh();
+    assert_eq!(mesh.unique_vertices(), 7);
+    assert_eq!(mesh.triangle_vertices(), &mesh.vertices[..6]);
+    assert_eq!(mesh.indices, [0, 1, 2, 3, 4, 5]);
+    assert_eq!(mesh.line_indices, [3, 4]);
+    assert_eq!(mesh.point_indices, [3, 6]);
+    assert_eq!(mesh.vertices[3].material, Some(0));
+}
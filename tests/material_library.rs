@@ -0,0 +1,164 @@
+use std::fs;
+use scop::object::{ImageChannel, Material, Object};
+use scop::parser;
+use common::TempDir;
+
+mod common;
+
+/// Directory holding `scene.mtl`, unique to the test.
+fn directory(test: &str, library: &str) -> TempDir {
+    let directory = TempDir::new("mtl", test);
+    fs::write(directory.join("scene.mtl"), library).unwrap();
+    directory
+}
+
+/// Parses `source` against a `scene.mtl` holding `library`.
+fn parse(test: &str, library: &str, source: &str) -> Object {
+    let directory = directory(test, library);
+    parser::parse_str(&format!("mtllib scene.mtl\n{source}"), &directory)
+        .unwrap_or_else(|e| panic!("{e}"))
+}
+
+fn material<'a>(object: &'a Object, name: &str) -> &'a Material {
+    object.materials.iter()
+        .find(|material| material.name == name)
+        .unwrap_or_else(|| panic!("no material '{name}' in {:?}", object.materials))
+}
+
+#[test]
+fn library_materials_tag_faces() {
+    let library = "# two materials\n\
+        newmtl red  brick\nKa 0.1\nKd 1 0 0\nKs 0.5 0.5 0.5\nNs 10\nd 0.5\nillum 2\n\
+        newmtl glass\nTr 0.25\nNi 1.5 # comment\n";
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n\
+        usemtl red brick\nf 1 2 3\nusemtl glass\nf 1 2 3\nusemtl  red   brick \nf 1 2 3\nusemtl paint\nf 1 2 3\n";
+    let object = parse("tag", library, source);
+    let names: Vec<_> = object.materials.iter().map(|material| material.name.as_str()).collect();
+    assert_eq!(names, ["red brick", "glass", "paint"]);
+    let materials: Vec<_> = object.faces.iter().map(|face| face.material).collect();
+    assert_eq!(materials, [None, Some(0), Some(1), Some(0), Some(2)]);
+
+    let red = material(&object, "red brick");
+    assert_eq!(red.ambient, Some([0.1; 3]));
+    assert_eq!(red.diffuse, Some([1.0, 0.0, 0.0]));
+    assert_eq!(red.specular, Some([0.5; 3]));
+    assert_eq!(red.specular_exponent, Some(10.0));
+    assert_eq!(red.dissolve, Some(0.5));
+    assert_eq!(red.illumination, Some(2));
+    let glass = material(&object, "glass");
+    assert_eq!(glass.dissolve, Some(0.75));
+    assert_eq!(glass.optical_density, Some(1.5));
+    assert_eq!(material(&object, "paint"), &Material::new("paint".to_string()));
+}
+
+#[test]
+fn material_errors_are_reported() {
+    let directory = directory("errors", "Kd 1 0 0\n");
+    assert!(parser::parse_str("mtllib scene.mtl\n", &directory).is_err());
+    fs::write(directory.join("scene.mtl"), "newmtl red\nKd 1 0\n").unwrap();
+    assert!(parser::parse_str("mtllib scene.mtl\n", &directory).is_err());
+    assert!(parser::parse_str("usemtl\n", &directory).is_err());
+}
+
+#[test]
+fn texture_map_options() {
+    let library = "newmtl brick\n\
+        map_Kd -blendu off -blendv off -cc on -clamp on -bm 0.5 -boost 2 -imfchan m -texres 512 \
+        -type sphere -mm 0.1 0.9 -o 1 2 3 -s 2 2 -t 0.5 textures/red brick.png\n\
+        map_Ks -o 0.5 -s 2 -bm 3 specular.png\n\
+        bump -imfchan g -clamp off v\n\
+        decal s o l.png\n";
+    let object = parse("options", library, "");
+    let brick = material(&object, "brick");
+    let diffuse = brick.diffuse_map.as_ref().unwrap();
+    assert_eq!(diffuse.file, "textures/red brick.png");
+    assert!(!diffuse.blend_u && !diffuse.blend_v);
+    assert!(diffuse.color_correction && diffuse.clamp);
+    assert_eq!(diffuse.bump_multiplier, 0.5);
+    assert_eq!(diffuse.boost, Some(2.0));
+    assert_eq!(diffuse.channel, Some(ImageChannel::Matte));
+    assert_eq!(diffuse.resolution, Some(512));
+    assert_eq!(diffuse.projection.as_deref(), Some("sphere"));
+    assert_eq!(diffuse.range, [0.1, 0.9]);
+    assert_eq!(diffuse.offset, [1.0, 2.0, 3.0]);
+    assert_eq!(diffuse.scale, [2.0, 2.0, 1.0]);
+    assert_eq!(diffuse.turbulence, [0.5, 0.0, 0.0]);
+
+    let specular = brick.specular_map.as_ref().unwrap();
+    assert_eq!(specular.file, "specular.png");
+    assert_eq!(specular.offset, [0.5, 0.0, 0.0]);
+    assert_eq!(specular.scale, [2.0, 1.0, 1.0]);
+    assert_eq!(specular.bump_multiplier, 3.0);
+    assert!(specular.blend_u && !specular.clamp && specular.channel.is_none());
+
+    let bump = brick.bump_map.as_ref().unwrap();
+    assert_eq!(bump.file, "v");
+    assert_eq!(bump.channel, Some(ImageChannel::Green));
+    assert_eq!(brick.decal_map.as_ref().unwrap().file, "s o l.png");
+}
+
+#[test]
+fn image_channels() {
+    let channels = [
+        ("r", ImageChannel::Red),
+        ("g", ImageChannel::Green),
+        ("b", ImageChannel::Blue),
+        ("m", ImageChannel::Matte),
+        ("l", ImageChannel::Luminance),
+        ("z", ImageChannel::Depth),
+    ];
+    for (name, channel) in channels {
+        let object = parse("channels", &format!("newmtl channel\nmap_d -imfchan {name} alpha.png\n"), "");
+        let map = material(&object, "channel").dissolve_map.clone().unwrap();
+        assert_eq!(map.channel, Some(channel), "{name}");
+        assert_eq!(map.file, "alpha.png");
+    }
+}
+
+#[test]
+fn names_spelling_obj_keywords() {
+    let library = "newmtl s\nKd 1 0 0\nnewmtl o\nmap_Kd v\nnewmtl g usemtl f\nmap_Ka -imfchan l mtllib\n";
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl s\nf 1 2 3\nusemtl g usemtl f\nf 1 2 3\n";
+    let object = parse("keywords", library, source);
+    let names: Vec<_> = object.materials.iter().map(|material| material.name.as_str()).collect();
+    assert_eq!(names, ["s", "g usemtl f", "o"]);
+    let materials: Vec<_> = object.faces.iter().map(|face| face.material).collect();
+    assert_eq!(materials, [Some(0), Some(1)]);
+    assert_eq!(material(&object, "s").diffuse, Some([1.0, 0.0, 0.0]));
+    assert_eq!(material(&object, "o").diffuse_map.as_ref().unwrap().file, "v");
+    let ambient = material(&object, "g usemtl f").ambient_map.clone().unwrap();
+    assert_eq!(ambient.channel, Some(ImageChannel::Luminance));
+    assert_eq!(ambient.file, "mtllib");
+}
+
+#[test]
+fn texture_map_errors() {
+    for statement in ["map_Kd", "map_Kd -clamp on", "map_Kd -imfchan x a.png", "map_Kd -blendu yes a.png", "map_Kd -bm", "map_Kd -unknown a.png"] {
+        let directory = directory("map-errors", &format!("newmtl brick\n{statement}\n"));
+        assert!(parser::parse_str("mtllib scene.mtl\n", &directory).is_err(), "{statement}");
+    }
+}
+
+#[test]
+fn pbr_extension_keys() {
+    let library = "newmtl metal\nPr 0.25\nPm 1\nPs 0.5\nPc 0.1\nPcr 0.03\naniso 0.7\nanisor 0.2\n\
+        map_Pr rough.png\nmap_Pm -clamp on metal.png\nnorm -bm 2 normal.png\nKd 0.5 0.5 0.5\n";
+    let object = parse("pbr", library, "");
+    let metal = material(&object, "metal");
+    assert_eq!(metal.roughness, Some(0.25));
+    assert_eq!(metal.metallic, Some(1.0));
+    assert_eq!(metal.sheen, Some(0.5));
+    assert_eq!(metal.clearcoat_thickness, Some(0.1));
+    assert_eq!(metal.clearcoat_roughness, Some(0.03));
+    assert_eq!(metal.anisotropy, Some(0.7));
+    assert_eq!(metal.anisotropy_rotation, Some(0.2));
+    assert_eq!(metal.roughness_map.as_ref().unwrap().file, "rough.png");
+    let metallic_map = metal.metallic_map.as_ref().unwrap();
+    assert!(metallic_map.clamp && metallic_map.file == "metal.png");
+    let normal_map = metal.normal_map.as_ref().unwrap();
+    assert_eq!((normal_map.file.as_str(), normal_map.bump_multiplier), ("normal.png", 2.0));
+    assert_eq!(metal.diffuse, Some([0.5; 3]));
+
+    let directory = directory("pbr-errors", "newmtl metal\nPr rough\n");
+    assert!(parser::parse_str("mtllib scene.mtl\n", &directory).is_err());
+}
@@ -0,0 +1,84 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::path::Path;
+use scop::object::{NormalMode, Object};
+use scop::parser;
+
+/// Two unit squares folded at a right angle along their shared edge `2 3`,
+/// facing `+z` and `+x`.
+const FOLD: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 1 0 -1\nv 1 1 -1\n\
+    vn 0 1 0\ns 1\nf 1 2 3 4\nf 2 5 6 3\n";
+
+fn fold(mode: NormalMode) -> Object {
+    let mut object = parser::parse_str(FOLD, Path::new(".")).unwrap();
+    object.generate_normals(mode);
+    assert!(object.has_normals());
+    object
+}
+
+/// Normal of each corner of the face at `face`.
+fn normals(object: &Object, face: usize) -> Vec<[f32; 3]> {
+    object.faces[face].corners.iter()
+        .map(|corner| {
+            let normal = object.vertices_normal[corner.normal.unwrap()];
+            [normal.i, normal.j, normal.k]
+        })
+        .collect()
+}
+
+fn assert_close(actual: &[[f32; 3]], expected: &[[f32; 3]]) {
+    let close = actual.iter().flatten()
+        .zip(expected.iter().flatten())
+        .all(|(a, b)| (a - b).abs() < 1e-6);
+    assert!(close && actual.len() == expected.len(), "{actual:?} != {expected:?}");
+}
+
+#[test]
+fn flat_normals_follow_each_face() {
+    let object = fold(NormalMode::Flat);
+    assert_close(&normals(&object, 0), &[[0.0, 0.0, 1.0]; 4]);
+    assert_close(&normals(&object, 1), &[[1.0, 0.0, 0.0]; 4]);
+}
+
+#[test]
+fn smooth_normals_average_over_the_shared_edge() {
+    let object = fold(NormalMode::Smooth);
+    let z = [0.0, 0.0, 1.0];
+    let x = [1.0, 0.0, 0.0];
+    let edge = [FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2];
+    assert_close(&normals(&object, 0), &[z, edge, edge, z]);
+    assert_close(&normals(&object, 1), &[edge, x, x, edge]);
+    assert_eq!(object.faces[0].corners[1].normal, object.faces[1].corners[0].normal);
+}
+
+#[test]
+fn explicit_normals_are_kept() {
+    for mode in [NormalMode::Flat, NormalMode::Smooth] {
+        // Faces mixing corners with and without normals are only built in code.
+        let mut object = parser::parse_str(FOLD, Path::new(".")).unwrap();
+        object.faces[0].corners[1].normal = Some(0);
+        object.generate_normals(mode);
+        assert_eq!(object.faces[0].corners[1].normal, Some(0), "{mode:?}");
+        assert_close(&normals(&object, 0)[1..2], &[[0.0, 1.0, 0.0]]);
+        assert_close(&normals(&object, 0)[..1], &[[0.0, 0.0, 1.0]]);
+    }
+}
+
+#[test]
+fn smooth_all_ignores_smoothing_groups() {
+    let z = [0.0, 0.0, 1.0];
+    let x = [1.0, 0.0, 0.0];
+    let edge = [FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2];
+    for groups in ["f 1 2 3 4\n", "s 1\nf 1 2 3 4\ns 2\n"] {
+        let source = FOLD.replace("s 1\nf 1 2 3 4\n", groups);
+        let parse = || parser::parse_str(&source, Path::new(".")).unwrap();
+        let mut object = parse();
+        object.generate_normals(NormalMode::Smooth);
+        assert_close(&normals(&object, 0), &[z; 4]);
+        assert_close(&normals(&object, 1), &[x; 4]);
+
+        let mut object = parse();
+        object.generate_normals(NormalMode::SmoothAll);
+        assert_close(&normals(&object, 0), &[z, edge, edge, z]);
+        assert_close(&normals(&object, 1), &[edge, x, x, edge]);
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::Write;
+use std::path::Path;
+use scop::parser;
+
+/// Strips of quads using relative indices, spread over objects, groups,
+/// smoothing groups and materials, with lines, points, continued lines and
+/// bilinear patches.
+fn source(strips: usize) -> String {
+    let mut source = String::from("# generated\r\n");
+    for strip in 0..strips {
+        if strip % 3 == 0 {
+            writeln!(source, "o strip {strip}").unwrap();
+        }
+        writeln!(source, "g part{} shared", strip % 2).unwrap();
+        writeln!(source, "usemtl material{}", strip % 4).unwrap();
+        writeln!(source, "s {}", if strip % 5 == 0 { "off".to_string() } else { strip.to_string() }).unwrap();
+        for i in 0..8 {
+            writeln!(source, "v {} {}.5 \\\n  {}", i, strip, -(i as f32) / 3.0).unwrap();
+            writeln!(source, "vt {} {}", i as f32 / 7.0, strip % 2).unwrap();
+        }
+        writeln!(source, "vn 0 0 1").unwrap();
+        for i in 0..3 {
+            let first = -8 + 2 * i;
+            writeln!(
+                source,
+                "f {a}/{a}/-1 {b}/{b}/-1 {c}/{c}/-1 {d}/{d}/-1",
+                a = first, b = first + 2, c = first + 3, d = first + 1,
+            ).unwrap();
+        }
+        writeln!(source, "l -8/-8 -6/-6 -4/-4").unwrap();
+        writeln!(source, "p -1 -2").unwrap();
+        if strip % 7 == 0 {
+            writeln!(source, "cstype bezier\ndeg 1 1\nsurf 0 1 0 1 -8 -6 -7 -5").unwrap();
+            writeln!(source, "parm u 0 1\nparm v 0 1\nend").unwrap();
+        }
+    }
+    source
+}
+
+#[test]
+fn parallel_parse_matches_sequential_parse() {
+    let source = source(200);
+    let sequential = parser::parse_str(&source, Path::new(".")).expect("Source is valid");
+    let patches = (0..200).step_by(7).count();
+    assert_eq!(sequential.surfaces.len(), patches);
+    assert_eq!(sequential.faces.len(), 600 + patches * 2 * parser::DEFAULT_RESOLUTION.pow(2));
+    for threads in 1..=9 {
+        let parallel = parser::parse_str_parallel(&source, Path::new("."), threads)
+            .expect("Source is valid");
+        assert_eq!(parallel, sequential, "{threads} threads");
+    }
+}
+
+#[test]
+fn parallel_parse_reports_sequential_errors() {
+    let mut source = source(50);
+    source += "f 1 2 -10000\n";
+    source += &self::source(50);
+    let sequential = parser::parse_str(&source, Path::new("."))
+        .expect_err("Index is out of bounds");
+    for threads in [2, 4, 7] {
+        let parallel = parser::parse_str_parallel(&source, Path::new("."), threads)
+            .expect_err("Index is out of bounds");
+        assert_eq!(parallel.to_string(), sequential.to_string());
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use scop::object::SMOOTHING_GROUP_OFF;
+use scop::error::ParseError;
+use scop::parser::{self, ParseMode, ParseOptions};
+use common::TempDir;
+
+mod common;
+
+/// Quad with a material from `scene.mtl`, over continued and CRLF lines.
+const SCENE: &str = "mtllib scene.mtl\r\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\n\
+    o quad\ng front\nusemtl red\nf 1/1/1 2/1/1 \\\n3/1/1 4/1/1\r\nl 1 3\n";
+
+/// Directory holding `scene.obj` and `scene.mtl`, unique to the test.
+fn directory(test: &str) -> TempDir {
+    let directory = TempDir::new("parser", test);
+    fs::write(directory.join("scene.mtl"), "newmtl red\nKd 1 0 0\nmap_Kd red.png\n").unwrap();
+    fs::write(directory.join("scene.obj"), SCENE).unwrap();
+    directory
+}
+
+#[test]
+fn smoothing_groups_apply_to_following_faces() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+        f 1 2 3\ns 1\nf 1 2 3\nf 1 2 3\ns off\nf 1 2 3\ns 4\nf 1 2 3\ns 0\nf 1 2 3\n";
+    let object = parser::parse_str(source, Path::new(".")).unwrap();
+    let groups: Vec<_> = object.faces.iter().map(|face| face.smoothing_group).collect();
+    assert_eq!(groups, [SMOOTHING_GROUP_OFF, 1, 1, SMOOTHING_GROUP_OFF, 4, SMOOTHING_GROUP_OFF]);
+    let smooth: Vec<_> = object.faces.iter().map(|face| face.is_smooth()).collect();
+    assert_eq!(smooth, [false, true, true, false, true, false]);
+
+    assert!(parser::parse_str("s on\n", Path::new(".")).is_err());
+    assert!(parser::parse_str("s 1 2\n", Path::new(".")).is_err());
+}
+
+#[test]
+fn entry_points_agree() {
+    let directory = directory("entry-points");
+    let path = directory.join("scene.obj").to_string_lossy().into_owned();
+    let parsed = parser::parse_with(&path, &ParseOptions::default()).unwrap();
+    assert!(parsed.warnings.is_empty());
+    let object = parsed.object;
+    assert_eq!(object.materials[0].diffuse, Some([1.0, 0.0, 0.0]));
+    assert_eq!(object.faces[0].corners.len(), 4);
+
+    assert_eq!(parser::parse(&path).unwrap(), object);
+    assert_eq!(parser::parse_str(SCENE, &directory).unwrap(), object);
+    assert_eq!(parser::parse_reader(Cursor::new(SCENE.as_bytes()), &directory).unwrap(), object);
+
+    // Without the directory the library is not found.
+    assert!(parser::parse_str(SCENE, Path::new(".")).is_err());
+}
+
+#[test]
+fn lenient_mode_skips_bad_statements() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nshade flat\nv 1 x 0\nf 1 2 3\nf 1 2 9\nf 3 2 1\n";
+    let options = ParseOptions {
+        mode: ParseMode::Lenient,
+        ..ParseOptions::default()
+    };
+    let parsed = parser::parse_str_with(source, Path::new("."), &options).unwrap();
+    assert_eq!(parsed.object.vertices.len(), 3);
+    let faces: Vec<_> = parsed.object.faces.iter()
+        .map(|face| face.corners.iter().map(|corner| corner.vertex).collect::<Vec<_>>())
+        .collect();
+    assert_eq!(faces, [[0, 1, 2], [2, 1, 0]]);
+    let lines: Vec<_> = parsed.warnings.iter()
+        .map(|warning| match &warning.0 {
+            ParseError::Syntax { line, source_line, .. } => (*line, source_line.as_str()),
+            error => panic!("unexpected warning {error:?}"),
+        })
+        .collect();
+    assert_eq!(lines, [(4, "shade flat"), (5, "v 1 x 0"), (7, "f 1 2 9")]);
+    assert!(parsed.warnings[2].to_string().starts_with("warning"), "{}", parsed.warnings[2]);
+
+    match parser::parse_str(source, Path::new(".")) {
+        Err(ParseError::Syntax { line: 4, .. }) => (),
+        result => panic!("strict mode gave {result:?}"),
+    }
+}
+
+#[test]
+fn syntax_errors_point_at_the_token() {
+    let error = parser::parse_str("v 0 0 0\n\tv 1 x 0\n", Path::new(".")).unwrap_err();
+    assert!(matches!(error, ParseError::Syntax { line: 2, column: 6, .. }), "{error:?}");
+    assert_eq!(error.to_string(), "error: invalid float literal\n --> <input>:2:6\n  |\n2 | \tv 1 x 0\n  | \t    ^");
+
+    // The gutter widens with the line number.
+    let source = format!("{}f 1 2  12\n", "v 0 0 0\n".repeat(9));
+    let error = parser::parse_str(&source, Path::new(".")).unwrap_err();
+    let rendered = error.to_string();
+    let lines: Vec<_> = rendered.lines().skip(1).collect();
+    assert_eq!(lines, ["  --> <input>:10:8", "   |", "10 | f 1 2  12", "   |        ^"]);
+}
+
+#[test]
+fn face_indices_are_one_based_or_relative() {
+    let vertices = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\n";
+    let object = parser::parse_str(&format!("{vertices}f 1/1 2/2 3/2\nf -3/-2 -1/-1 -2/-1\n"), Path::new(".")).unwrap();
+    let corners: Vec<_> = object.faces.iter()
+        .map(|face| face.corners.iter().map(|corner| (corner.vertex, corner.texture)).collect::<Vec<_>>())
+        .collect();
+    assert_eq!(corners, [[(0, Some(0)), (1, Some(1)), (2, Some(1))], [(0, Some(0)), (2, Some(1)), (1, Some(1))]]);
+
+    for (face, column) in [("f 0 1 2", 3), ("f 1 2 -4", 7), ("f 1 2 4", 7), ("f 1/0 2/1 3/1", 3)] {
+        match parser::parse_str(&format!("{vertices}{face}\n"), Path::new(".")) {
+            Err(ParseError::Syntax { line: 6, column: actual, .. }) => assert_eq!(actual, column, "{face}"),
+            result => panic!("{face} gave {result:?}"),
+        }
+    }
+    let error = parser::parse_str("v 0 0 0\nf 0 1 1\n", Path::new(".")).unwrap_err();
+    assert!(error.to_string().contains("Index 0 is invalid"), "{error}");
+}
+
+#[test]
+fn lines_and_points() {
+    let vertices = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\n";
+    let object = parser::parse_str(&format!("{vertices}l 1 2 3 1\nl 1/1 -1/2\np 1 -1\np 2\n"), Path::new(".")).unwrap();
+    let lines: Vec<_> = object.lines.iter()
+        .map(|line| line.corners.iter().map(|corner| (corner.vertex, corner.texture, corner.normal)).collect::<Vec<_>>())
+        .collect();
+    assert_eq!(lines, [
+        vec![(0, None, None), (1, None, None), (2, None, None), (0, None, None)],
+        vec![(0, Some(0), None), (2, Some(1), None)],
+    ]);
+    assert_eq!(object.lines[0].segments().count(), 3);
+    let points: Vec<_> = object.points.iter().map(|point| point.vertices.clone()).collect();
+    assert_eq!(points, [vec![0, 2], vec![1]]);
+    assert!(object.faces.is_empty());
+
+    let errors = [("l 1", 1), ("l", 1), ("l 1 2/1", 5), ("l 1/1 2", 7), ("l 1//1 2", 3), ("l 1 4", 5), ("l 1/3 2/1", 3), ("p", 1), ("p 1/1", 3), ("p 4", 3)];
+    for (statement, column) in errors {
+        match parser::parse_str(&format!("{vertices}{statement}\n"), Path::new(".")) {
+            Err(ParseError::Syntax { line: 6, column: actual, .. }) => assert_eq!(actual, column, "{statement}"),
+            result => panic!("{statement} gave {result:?}"),
+        }
+    }
+}
+
+#[test]
+fn byte_order_mark_is_skipped() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let expected = parser::parse_str(source, Path::new(".")).unwrap();
+    let with_mark = format!("\u{feff}{source}");
+    assert_eq!(parser::parse_str(&with_mark, Path::new(".")).unwrap(), expected);
+    assert_eq!(parser::parse_reader(Cursor::new(with_mark.as_bytes()), Path::new(".")).unwrap(), expected);
+}
+
+#[test]
+fn invalid_utf8_is_located() {
+    let source = b"v 0 0 0\nv 1 0 0\nv 0 1 0\no caf\xe9 \\\n  bar\nf 1 2 3\n";
+    match parser::parse_reader(Cursor::new(source), Path::new(".")) {
+        Err(ParseError::Syntax { line: 4, column: 6, source_line, .. }) => assert_eq!(source_line, "o caf\u{fffd} \\"),
+        result => panic!("invalid UTF-8 gave {result:?}"),
+    }
+
+    let options = ParseOptions {
+        mode: ParseMode::Lenient,
+        ..ParseOptions::default()
+    };
+    let parsed = parser::parse_reader_with(Cursor::new(source), Path::new("."), &options).unwrap();
+    assert_eq!(parsed.warnings.len(), 1);
+    assert!(matches!(parsed.warnings[0].0, ParseError::Syntax { line: 4, column: 6, .. }), "{:?}", parsed.warnings);
+    assert_eq!(parsed.object.objects[0].name, "caf\u{fffd} bar");
+    assert_eq!(parsed.object.faces.len(), 1);
+}
@@ -0,0 +1,145 @@
+use scop::object::{Corner, Face, Object, Vertex, VertexNormal, VertexTexture};
+use scop::ply::{self, PlyFormat, ScalarType, VertexAttribute};
+
+/// Square of two triangles whose vertices have a colour, a normal and a
+/// confidence.
+fn scan() -> (Object, Vec<VertexAttribute>) {
+    let mut object = Object::new();
+    for (i, [x, y]) in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].into_iter().enumerate() {
+        let mut vertex = Vertex::new(x, y, 0.5, None);
+        vertex.color = Some([i as f32 / 3.0, 1.0, 0.0]);
+        object.add_vertex(vertex);
+        object.add_vertex_normal(VertexNormal::new(0.0, 0.0, 1.0));
+    }
+    for indices in [[0, 1, 2], [0, 2, 3]] {
+        let mut face = Face::new();
+        face.corners = indices.into_iter()
+            .map(|vertex| Corner { vertex, texture: None, normal: Some(vertex) })
+            .collect();
+        object.add_face(face);
+    }
+    let confidence = VertexAttribute {
+        name: "confidence".to_string(),
+        scalar: ScalarType::Float32,
+        values: vec![0.25, 0.5, 0.75, 1.0],
+    };
+    (object, vec![confidence])
+}
+
+#[test]
+fn ply_round_trip_keeps_colors_normals_and_attributes() {
+    let (object, attributes) = scan();
+    for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian, PlyFormat::BinaryBigEndian] {
+        let mut bytes = vec![];
+        ply::write(&object, &attributes, &mut bytes, format).unwrap();
+        let parsed = ply::parse_bytes(&bytes, "scan.ply").unwrap();
+        assert_eq!(parsed.object.faces, object.faces, "{format:?}");
+        assert_eq!(parsed.object.vertices_normal, object.vertices_normal, "{format:?}");
+        assert_eq!(parsed.attributes, attributes, "{format:?}");
+        for (parsed, vertex) in parsed.object.vertices.iter().zip(&object.vertices) {
+            assert_eq!(parsed.position(), vertex.position(), "{format:?}");
+            let [parsed, color] = [parsed.color.unwrap(), vertex.color.unwrap()];
+            assert!(parsed.iter().zip(color).all(|(a, b)| (a - b).abs() <= 0.5 / 255.0), "{format:?}");
+        }
+    }
+}
+
+#[test]
+fn corners_with_distinct_texture_coordinates_split_vertices() {
+    let (mut object, attributes) = scan();
+    object.add_vertex_texture(VertexTexture::new(0.0, Some(0.0), None));
+    object.add_vertex_texture(VertexTexture::new(1.0, Some(1.0), None));
+    object.faces[1].corners[0].texture = Some(1);
+    let mut bytes = vec![];
+    ply::write(&object, &attributes, &mut bytes, PlyFormat::Ascii).unwrap();
+    let parsed = ply::parse_bytes(&bytes, "split.ply").unwrap();
+    assert_eq!(parsed.object.vertices.len(), 5);
+    assert_eq!(parsed.object.faces[1].corners[0].vertex, 4);
+    assert_eq!(parsed.object.vertices_texture[4], VertexTexture::new(1.0, Some(1.0), None));
+    assert_eq!(parsed.object.vertices[4].position(), object.vertices[0].position());
+    assert_eq!(parsed.attribute("confidence").unwrap().values[4], 0.25);
+}
+
+#[test]
+fn big_endian_point_cloud_with_integer_properties() {
+    let mut bytes = b"ply\nformat binary_big_endian 1.0\ncomment scanner output\n\
+        element vertex 2\nproperty double x\nproperty double y\nproperty double z\n\
+        property ushort red\nproperty ushort green\nproperty ushort blue\nproperty int16 intensity\n\
+        element camera 1\nproperty list uint8 float32 pose\n\
+        end_header\n".to_vec();
+    for (position, color, intensity) in [([1.0f64, 2.0, 3.0], [65535u16, 0, 0], -7i16), ([4.0, 5.0, 6.0], [0, 0, 65535], 12)] {
+        position.iter().for_each(|value| bytes.extend(value.to_be_bytes()));
+        color.iter().for_each(|value| bytes.extend(value.to_be_bytes()));
+        bytes.extend(intensity.to_be_bytes());
+    }
+    bytes.push(2);
+    [0.5f32, 1.5].iter().for_each(|value| bytes.extend(value.to_be_bytes()));
+
+    let parsed = ply::parse_bytes(&bytes, "cloud.ply").unwrap();
+    let object = &parsed.object;
+    assert_eq!(object.vertices[1].position(), [4.0, 5.0, 6.0]);
+    assert_eq!(object.vertices[0].color, Some([1.0, 0.0, 0.0]));
+    assert_eq!(object.points.len(), 1);
+    assert_eq!(object.points[0].vertices, [0, 1]);
+    assert_eq!(parsed.attribute("intensity").unwrap().values, [-7.0, 12.0]);
+    assert_eq!(parsed.attribute("intensity").unwrap().scalar, ScalarType::Int16);
+
+    let truncated = ply::parse_bytes(&bytes[..bytes.len() - 1], "cloud.ply").unwrap_err().to_string();
+    assert!(truncated.contains("cloud.ply") && truncated.contains("end of data"), "{truncated}");
+}
+
+#[test]
+fn ascii_errors_point_at_the_file_line() {
+    let source = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+        element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+        0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+    assert_eq!(ply::parse_bytes(source.as_bytes(), "tri.ply").unwrap().object.faces.len(), 1);
+
+    let error = ply::parse_bytes(source.replace("3 0 1 2", "3 0 1 3").as_bytes(), "tri.ply")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("tri.ply:13:"), "{error}");
+    let error = ply::parse_bytes(source.replace("1 0 0\n", "1 0\n").as_bytes(), "tri.ply")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("tri.ply:11:"), "{error}");
+    let error = ply::parse_bytes(source.replace("float y", "vector y").as_bytes(), "tri.ply")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("tri.ply:5:") && error.contains("Unknown type 'vector'"), "{error}");
+}
+
+#[test]
+fn header_counts_are_not_trusted() {
+    let source = "ply\nformat ascii 1.0\nelement vertex 1000000000000000000\n\
+        property float x\nproperty float y\nproperty float z\nproperty float confidence\nend_header\n\
+        0 0 0 1\n";
+    let error = ply::parse_bytes(source.as_bytes(), "huge.ply").unwrap_err().to_string();
+    assert!(error.contains("huge.ply"), "{error}");
+    let binary = source.replace("ascii", "binary_little_endian").replace("0 0 0 1\n", "");
+    let mut bytes = binary.into_bytes();
+    bytes.extend([0u8; 16]);
+    assert!(ply::parse_bytes(&bytes, "huge.ply").is_err());
+}
+
+#[test]
+fn float_indices_must_be_integers() {
+    let source = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+        element face 1\nproperty list uchar float vertex_indices\nend_header\n\
+        0 0 0\n1 0 0\n0 1 0\n3 0 1 2.0\n";
+    assert_eq!(ply::parse_bytes(source.as_bytes(), "tri.ply").unwrap().object.faces.len(), 1);
+    for index in ["1.5", "nan", "inf", "-0.5"] {
+        let error = ply::parse_bytes(source.replace("2.0\n", &format!("{index}\n")).as_bytes(), "tri.ply")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("tri.ply:13:") && error.contains("not an integer"), "{index}: {error}");
+    }
+}
+
+#[test]
+fn header_lines_ending_with_a_backslash_stand_alone() {
+    let source = "ply\r\nformat ascii 1.0\r\ncomment saved to C:\\scans\\\r\nelement vertex 1\r\n\
+        property float x\r\nproperty float y\r\nproperty float z\r\nend_header\r\n1 2 3\r\n";
+    let parsed = ply::parse_bytes(source.as_bytes(), "scan.ply").unwrap();
+    assert_eq!(parsed.object.vertices[0].position(), [1.0, 2.0, 3.0]);
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+use scop::object::Object;
+use scop::parser;
+use scop::stl::{self, StlFormat};
+
+const CUBE: &str = "o cube\n\
+    v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\n\
+    f 1 4 3 2\nf 5 6 7 8\nf 1 2 6 5\nf 2 3 7 6\nf 3 4 8 7\nf 4 1 5 8\n";
+
+fn cube() -> Object {
+    parser::parse_str(CUBE, Path::new(".")).unwrap()
+}
+
+/// Triangles of `object` as positions, for comparisons across re-indexing.
+fn triangles(object: &Object) -> Vec<[[f32; 3]; 3]> {
+    let mut object = object.clone();
+    object.triangulate();
+    object.faces.iter()
+        .map(|face| std::array::from_fn(|i| object.vertices[face.corners[i].vertex].position()))
+        .collect()
+}
+
+#[test]
+fn stl_round_trip_welds_vertices_and_keeps_facet_normals() {
+    let cube = cube();
+    for format in [StlFormat::Ascii, StlFormat::Binary] {
+        let mut bytes = vec![];
+        stl::write(&cube, &mut bytes, format).unwrap();
+        let parsed = stl::parse_bytes(&bytes, "cube.stl").unwrap();
+        assert_eq!(parsed.vertices.len(), 8, "{format:?}");
+        assert_eq!(parsed.vertices_normal.len(), 6, "{format:?}");
+        assert_eq!(triangles(&parsed), triangles(&cube), "{format:?}");
+        for face in &parsed.faces {
+            let normal = parsed.vertices_normal[face.corners[0].normal.unwrap()];
+            let [x, y, z] = face.area_normal(&parsed.vertices);
+            let length = (x * x + y * y + z * z).sqrt();
+            assert_eq!([normal.i, normal.j, normal.k], [x / length, y / length, z / length]);
+        }
+        let names: Vec<_> = parsed.objects.iter().map(|range| range.name.as_str()).collect();
+        match format {
+            StlFormat::Ascii => assert_eq!(names, ["cube"]),
+            StlFormat::Binary => assert!(names.is_empty()),
+        }
+    }
+}
+
+#[test]
+fn binary_header_starting_with_solid_is_detected() {
+    let mut bytes = vec![];
+    stl::write(&cube(), &mut bytes, StlFormat::Binary).unwrap();
+    bytes[..11].copy_from_slice(b"solid cube ");
+    let parsed = stl::parse_bytes(&bytes, "cube.stl").unwrap();
+    assert_eq!(triangles(&parsed), triangles(&cube()));
+    let truncated = stl::parse_bytes(&bytes[..bytes.len() - 1], "cube.stl");
+    assert!(truncated.is_err());
+}
+
+#[test]
+fn ascii_stl_welds_signed_zeros_and_reports_errors() {
+    let source = "solid\n\
+        facet normal 0 0 0\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\nendloop\nendfacet\n\
+        facet normal 0 0 -1\nouter loop\nvertex -0 -0 -0\nvertex 0 1 0\nvertex 1 0 0\nendloop\nendfacet\n\
+        endsolid\n";
+    let parsed = stl::parse_bytes(source.as_bytes(), "zeros.stl").unwrap();
+    assert_eq!(parsed.vertices.len(), 3);
+    assert_eq!(parsed.faces[0].corners[0].normal, None);
+    assert_eq!(parsed.faces[1].corners[0].normal, Some(0));
+
+    let error = stl::parse_bytes(source.replace("vertex 0 1 0\nendloop", "endloop").as_bytes(), "short.stl")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Facet with 2 vertices") && error.contains("short.stl:6:"), "{error}");
+    let error = stl::parse_bytes(source.replace("endsolid\n", "").as_bytes(), "open.stl")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("open.stl:15:"), "{error}");
+
+    // Lines ending with a backslash do not continue onto the next one.
+    let windows = source.replacen("solid\n", "solid C:\\scans\\\r\n", 1);
+    let parsed = stl::parse_bytes(windows.as_bytes(), "windows.stl").unwrap();
+    assert_eq!(parsed.faces.len(), 2);
+    let error = stl::parse_bytes(windows.replace("endsolid\n", "").as_bytes(), "windows.stl")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("windows.stl:15:"), "{error}");
+}
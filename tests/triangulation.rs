@@ -0,0 +1,79 @@
+use std::f32::consts::PI;
+use std::path::Path;
+use scop::object::Object;
+use scop::parser;
+
+/// L-shaped hexagon, concave at its fourth corner.
+const HEXAGON: [[f32; 2]; 6] = [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [1.0, 1.0], [1.0, 2.0], [0.0, 2.0]];
+
+/// Five-pointed star, alternating between radii 2 and 1.
+fn star() -> Vec<[f32; 2]> {
+    (0..10)
+        .map(|i| {
+            let radius = if i % 2 == 0 { 2.0 } else { 1.0 };
+            let angle = i as f32 * PI / 5.0;
+            [radius * angle.cos(), radius * angle.sin()]
+        })
+        .collect()
+}
+
+/// Single face over `points`, laid on the tilted plane `z = x` and wound in
+/// their order or reversed. Corner `i` has texture coordinate and normal `i`.
+fn polygon(points: &[[f32; 2]], reversed: bool) -> Object {
+    let mut source = String::new();
+    for (i, [x, y]) in points.iter().enumerate() {
+        source += &format!("v {x} {y} {x}\nvt {i} 0\nvn 0 0 {}\n", i + 1);
+    }
+    let mut corners: Vec<_> = (1..=points.len()).collect();
+    if reversed {
+        corners.reverse();
+    }
+    source += "f";
+    for i in corners {
+        source += &format!(" {i}/{i}/{i}");
+    }
+    parser::parse_str(&source, Path::new(".")).unwrap()
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+/// Triangulates the polygon both ways round, checking the triangles cover it
+/// with its winding and keep the attributes of its corners.
+fn check(points: &[[f32; 2]]) {
+    for reversed in [false, true] {
+        let object = polygon(points, reversed);
+        let face = &object.faces[0];
+        let normal = face.area_normal(&object.vertices);
+        let triangles = face.triangulate(&object.vertices);
+        assert_eq!(triangles.len(), points.len() - 2, "reversed: {reversed}");
+
+        let mut area = 0.0;
+        for triangle in &triangles {
+            let triangle_normal = triangle.area_normal(&object.vertices);
+            assert!(dot(triangle_normal, normal) > 0.0, "{triangle:?} reversed: {reversed}");
+            area += length(triangle_normal) / 2.0;
+            for corner in &triangle.corners {
+                assert_eq!(corner.texture, Some(corner.vertex), "reversed: {reversed}");
+                assert_eq!(corner.normal, Some(corner.vertex), "reversed: {reversed}");
+            }
+        }
+        let expected = length(normal) / 2.0;
+        assert!((area - expected).abs() < 1e-4 * expected, "{area} != {expected} reversed: {reversed}");
+    }
+}
+
+#[test]
+fn concave_hexagon_triangulates() {
+    check(&HEXAGON);
+}
+
+#[test]
+fn star_triangulates() {
+    check(&star());
+}
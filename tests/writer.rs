@@ -0,0 +1,280 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use proptest::prelude::*;
+use proptest::num::f32::{NORMAL, SUBNORMAL, ZERO};
+use scop::object::{
+    Corner, Face, ImageChannel, Line, Material, Object, Point, TextureMap, Vertex, VertexNormal,
+    VertexParameter, VertexTexture,
+};
+use scop::parser;
+use scop::writer::{self, IndexMode, WriteOptions};
+use common::TempDir;
+
+mod common;
+
+const LIBRARY: &str = "round_trip.mtl";
+/// Names given to the objects, groups and materials, several spelling OBJ
+/// keywords.
+const OBJECT_NAMES: [&str; 4] = ["object 0", "end", "step one", "v vt"];
+const GROUP_NAMES: [&str; 5] = ["group0", "g", "curv", "s", "usemtl"];
+const MATERIAL_NAMES: [&str; 5] = ["material0", "f", "usemtl off", "material3", "end"];
+
+/// Statement of a generated file, indices being reduced modulo the length of
+/// the lists they refer to.
+#[derive(Debug, Clone)]
+enum Statement {
+    Object(usize),
+    Groups(Vec<usize>),
+    SmoothingGroup(u32),
+    Material(usize),
+    Face(Vec<usize>, bool, bool),
+    Line(Vec<usize>, bool),
+    Point(Vec<usize>),
+}
+
+fn number() -> impl Strategy<Value = f32> {
+    NORMAL | SUBNORMAL | ZERO
+}
+
+fn optional_pair() -> impl Strategy<Value = Option<(f32, Option<f32>)>> {
+    prop::option::of((number(), prop::option::of(number())))
+}
+
+fn statement() -> impl Strategy<Value = Statement> {
+    let indices = |min| prop::collection::vec(any::<usize>(), min..7);
+    prop_oneof![
+        1 => (0..4usize).prop_map(Statement::Object),
+        1 => prop::collection::vec(0..5usize, 1..4).prop_map(Statement::Groups),
+        1 => (0..4u32).prop_map(Statement::SmoothingGroup),
+        1 => (0..5usize).prop_map(Statement::Material),
+        4 => (indices(3), any::<bool>(), any::<bool>()).prop_map(|(i, t, n)| Statement::Face(i, t, n)),
+        1 => (indices(2), any::<bool>()).prop_map(|(i, t)| Statement::Line(i, t)),
+        1 => indices(1).prop_map(Statement::Point),
+    ]
+}
+
+fn texture_map() -> impl Strategy<Value = TextureMap> {
+    (
+        (0..3usize, any::<bool>(), any::<bool>(), any::<bool>(), prop::option::of(number())),
+        (prop::option::of(0..6u8), prop::option::of(any::<u32>()), prop::option::of(number())),
+        ([number(), number()], [number(), number(), number()], any::<bool>()),
+    ).prop_map(|((file, blend_u, cc, clamp, boost), (channel, resolution, bump), (range, offset, sphere))| {
+        let mut map = TextureMap::new(format!("texture {file}.png"));
+        map.blend_u = blend_u;
+        map.color_correction = cc;
+        map.clamp = clamp;
+        map.boost = boost;
+        map.channel = channel.map(|channel| [
+            ImageChannel::Red,
+            ImageChannel::Green,
+            ImageChannel::Blue,
+            ImageChannel::Matte,
+            ImageChannel::Luminance,
+            ImageChannel::Depth,
+        ][channel as usize]);
+        map.resolution = resolution;
+        map.bump_multiplier = bump.unwrap_or(1.0);
+        map.range = range;
+        map.offset = offset;
+        map.projection = sphere.then(|| "sphere".to_string());
+        map
+    })
+}
+
+fn material(name: usize) -> impl Strategy<Value = Material> {
+    (
+        prop::option::of([number(), number(), number()]),
+        prop::option::of([number(), number(), number()]),
+        prop::option::of(number()),
+        prop::option::of(number()),
+        prop::option::of(0..11u32),
+        prop::option::of(texture_map()),
+        prop::collection::vec(texture_map(), 0..2),
+    ).prop_map(move |(diffuse, emissive, specular_exponent, roughness, illumination, diffuse_map, reflection_maps)| {
+        let mut material = Material::new(MATERIAL_NAMES[name].to_string());
+        material.diffuse = diffuse;
+        material.emissive = emissive;
+        material.specular_exponent = specular_exponent;
+        material.roughness = roughness;
+        material.illumination = illumination;
+        material.diffuse_map = diffuse_map;
+        material.reflection_maps = reflection_maps;
+        material
+    })
+}
+
+prop_compose! {
+    /// Object built by the same calls the parser makes for each statement,
+    /// which is the form a parse gives back.
+    fn object()(
+        vertices in prop::collection::vec(
+            ([number(), number(), number()], prop::option::of(number()), prop::option::of([number(), number(), number()])),
+            1..12,
+        ),
+        textures in prop::collection::vec((number(), optional_pair()), 0..6),
+        normals in prop::collection::vec([number(), number(), number()], 0..6),
+        parameters in prop::collection::vec((number(), optional_pair()), 0..4),
+        statements in prop::collection::vec(statement(), 0..40),
+        library in (material(0), material(2), material(4)).prop_map(|(a, b, c)| vec![a, b, c]),
+    ) -> (Object, Vec<Material>) {
+        let mut object = Object::new();
+        object.material_libraries.push(LIBRARY.to_string());
+        for ([x, y, z], w, color) in vertices {
+            let mut vertex = Vertex::new(x, y, z, w);
+            vertex.color = color;
+            object.add_vertex(vertex);
+        }
+        for (u, vw) in textures {
+            object.add_vertex_texture(VertexTexture::new(u, vw.map(|vw| vw.0), vw.and_then(|vw| vw.1)));
+        }
+        for [i, j, k] in normals {
+            object.add_vertex_normal(VertexNormal::new(i, j, k));
+        }
+        for (u, vw) in parameters {
+            object.add_vertex_parameter(VertexParameter::new(u, vw.map(|vw| vw.0), vw.and_then(|vw| vw.1)));
+        }
+        let (mut smoothing_group, mut material) = (0, None);
+        for statement in statements {
+            let corners = |indices: &[usize], texture: bool, normal: bool, object: &Object| -> Vec<Corner> {
+                let texture = texture && !object.vertices_texture.is_empty();
+                let normal = normal && !object.vertices_normal.is_empty();
+                indices.iter().map(|&i| Corner {
+                    vertex: i % object.vertices.len(),
+                    texture: texture.then(|| i % object.vertices_texture.len()),
+                    normal: normal.then(|| i / 7 % object.vertices_normal.len()),
+                }).collect()
+            };
+            match statement {
+                Statement::Object(name) => object.begin_object(OBJECT_NAMES[name].to_string()),
+                Statement::Groups(names) => {
+                    let mut names: Vec<_> = names.iter().map(|&name| GROUP_NAMES[name].to_string()).collect();
+                    names.dedup();
+                    object.begin_groups(names);
+                }
+                Statement::SmoothingGroup(group) => smoothing_group = group,
+                Statement::Material(name) => material = Some(object.material_index(MATERIAL_NAMES[name])),
+                Statement::Face(indices, texture, normal) => {
+                    let face = Face {
+                        corners: corners(&indices, texture, normal, &object),
+                        smoothing_group,
+                        material,
+                    };
+                    object.add_face(face);
+                }
+                Statement::Line(indices, texture) => {
+                    let line = Line {
+                        corners: corners(&indices, texture, false, &object),
+                        material,
+                    };
+                    object.add_line(line);
+                }
+                Statement::Point(indices) => {
+                    let point = Point {
+                        vertices: indices.iter().map(|&i| i % object.vertices.len()).collect(),
+                        material,
+                    };
+                    object.add_point(point);
+                }
+            }
+        }
+        // As loading the library does, definitions replace the materials
+        // declared by `usemtl` and the others are appended.
+        for definition in &library {
+            match object.materials.iter_mut().find(|known| known.name == definition.name) {
+                Some(known) => *known = definition.clone(),
+                None => object.materials.push(definition.clone()),
+            }
+        }
+        (object, library)
+    }
+}
+
+/// Directory holding the material library, unique to the test.
+fn directory(test: &str) -> TempDir {
+    TempDir::new("writer", test)
+}
+
+fn round_trip(object: &Object, library: &[Material], directory: &Path, options: &WriteOptions) -> Object {
+    let mut materials = vec![];
+    writer::write_material_library(library, &mut materials, options).unwrap();
+    fs::write(directory.join(LIBRARY), materials).unwrap();
+    let mut source = vec![];
+    writer::write(object, &mut source, options).unwrap();
+    let source = String::from_utf8(source).unwrap();
+    parser::parse_str(&source, directory)
+        .unwrap_or_else(|e| panic!("{e}\n{source}"))
+}
+
+proptest! {
+    #[test]
+    fn parse_inverts_write((object, library) in object(), relative in any::<bool>()) {
+        let directory = directory("inverse");
+        let options = WriteOptions {
+            indices: if relative { IndexMode::Relative } else { IndexMode::Absolute },
+            ..WriteOptions::default()
+        };
+        let parsed = round_trip(&object, &library, &directory, &options);
+        prop_assert_eq!(parsed, object);
+    }
+
+    #[test]
+    fn triangulated_output_matches_triangulation((object, library) in object()) {
+        let directory = directory("triangulated");
+        let options = WriteOptions {
+            triangulate: true,
+            ..WriteOptions::default()
+        };
+        let parsed = round_trip(&object, &library, &directory, &options);
+        let mut triangulated = object;
+        triangulated.triangulate();
+        prop_assert_eq!(parsed, triangulated);
+    }
+}
+
+#[test]
+fn precision_rounds_numbers() {
+    let source = "v 0.123456 -2 1e-7\nvt 0.5\nf 1/1 1/1 1/1\n";
+    let directory = directory("precision");
+    let object = parser::parse_str(source, &directory).unwrap();
+    let options = WriteOptions {
+        precision: Some(3),
+        indices: IndexMode::Relative,
+        ..WriteOptions::default()
+    };
+    let mut written = vec![];
+    writer::write(&object, &mut written, &options).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), "v 0.123 -2.000 0.000\nvt 0.500\nf -1/-1 -1/-1 -1/-1\n");
+}
+
+#[test]
+fn faces_without_material_come_first() {
+    let object = parser::parse_str("v 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\nl 1 2\n", Path::new(".")).unwrap();
+    let mut written = vec![];
+    writer::write(&object, &mut written, &WriteOptions::default()).unwrap();
+
+    let mut object = object;
+    object.faces.push(Face { material: None, ..object.faces[0].clone() });
+    let error = writer::write(&object, &mut vec![], &WriteOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn free_form_elements_are_written_tessellated() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvn 0 0 1\no patch\ns 1\ncstype bezier\ndeg 1 1\n\
+        surf 0 1 0 1 1 2 3 4\nparm u 0 1\nparm v 0 1\nend\ng edge\ndeg 1\ncurv 0 1 1 4\nparm u 0 1\nend\nf 1//1 2//1 4//1\n";
+    let object = parser::parse_str(source, Path::new(".")).unwrap();
+    assert!(!object.faces.is_empty() && !object.lines.is_empty());
+    let mut written = vec![];
+    writer::write(&object, &mut written, &WriteOptions::default()).unwrap();
+    let written = String::from_utf8(written).unwrap();
+    assert!(!written.contains("surf") && !written.contains("curv"), "{written}");
+
+    let parsed = parser::parse_str(&written, Path::new(".")).unwrap_or_else(|e| panic!("{e}\n{written}"));
+    let tessellated = Object {
+        curves: vec![],
+        surfaces: vec![],
+        ..object
+    };
+    assert_eq!(parsed, tessellated);
+}